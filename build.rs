@@ -1,4 +1,30 @@
 fn main() {
     #[cfg(target_os = "windows")]
     println!("cargo:rustc-link-lib=advapi32");
+
+    // Versions of the whisper bindings this build links, shown in the app's About section
+    let lock = std::fs::read_to_string("Cargo.lock").unwrap_or_default();
+    for (package, var) in [
+        ("whisper-rs", "WHISPER_RS_VERSION"),
+        ("whisper-rs-sys", "WHISPER_RS_SYS_VERSION"),
+    ] {
+        println!(
+            "cargo:rustc-env={}={}",
+            var,
+            locked_version(&lock, package).unwrap_or("unknown")
+        );
+    }
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+/// The version Cargo.lock pins for a package
+fn locked_version<'a>(lock: &'a str, package: &str) -> Option<&'a str> {
+    let name = format!("name = \"{}\"", package);
+    let mut lines = lock.lines();
+    lines.find(|line| *line == name)?;
+    lines
+        .next()?
+        .strip_prefix("version = \"")?
+        .strip_suffix('"')
 }
@@ -0,0 +1,232 @@
+//! Re-timing existing subtitles: the known text of each cue is looked up in a fresh
+//! transcript of the audio, and the cue takes the times of the words it matched
+
+use crate::Segment;
+
+// Recognized words searched for each cue, counted from the end of the previous match
+const SEARCH_WINDOW_WORDS: usize = 500;
+
+// Local alignment scores: a shared word, and a word on either side left unmatched
+const MATCH_SCORE: i32 = 2;
+const MISMATCH_SCORE: i32 = -1;
+const GAP_SCORE: i32 = -1;
+
+/// Cues of an SRT file as segments, times in centiseconds. Blocks without a valid timing line are skipped.
+pub fn parse_srt(srt: &str) -> Vec<Segment> {
+    let srt = srt.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    srt.split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+            let (start, end) = lines.next()?.split_once("-->")?;
+            // Position settings may follow the end time
+            let end = end.split_whitespace().next()?;
+            let text: Vec<&str> = lines.map(str::trim).filter(|line| !line.is_empty()).collect();
+            Some(Segment {
+                start: parse_srt_timestamp(start.trim())?,
+                end: parse_srt_timestamp(end)?,
+                text: text.join(" "),
+                confidence: 1.0,
+                words: Vec::new(),
+                speaker_turn: false,
+                no_speech_prob: 0.0,
+            })
+        })
+        .collect()
+}
+
+/// "HH:MM:SS,mmm" (or with a period) in centiseconds
+fn parse_srt_timestamp(s: &str) -> Option<i64> {
+    let (hms, millis) = s.split_once([',', '.'])?;
+    let mut parts = hms.split(':').map(|p| p.parse::<i64>().ok());
+    let (h, m, sec) = (parts.next()??, parts.next()??, parts.next()??);
+    let millis: i64 = millis.parse().ok()?;
+    Some(((h * 60 + m) * 60 + sec) * 100 + millis / 10)
+}
+
+/// A recognized word, reduced to lowercase letters and digits for comparison
+struct TimedWord {
+    key: String,
+    start: i64,
+    end: i64,
+}
+
+fn word_key(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Every word of the transcript with its time span. DTW token timings are used when present;
+/// otherwise a segment's time is shared out among its words by length.
+fn timed_words(segments: &[Segment]) -> Vec<TimedWord> {
+    let mut words = Vec::new();
+    for segment in segments {
+        if !segment.words.is_empty() {
+            // Tokens that start with a space begin a new word
+            let mut current: Option<(String, i64, i64)> = None;
+            for token in &segment.words {
+                match current {
+                    Some((ref mut text, _, ref mut end)) if !token.text.starts_with(' ') => {
+                        text.push_str(&token.text);
+                        *end = token.end;
+                    }
+                    _ => {
+                        if let Some((text, start, end)) = current.take() {
+                            words.push(TimedWord {
+                                key: word_key(&text),
+                                start,
+                                end,
+                            });
+                        }
+                        current = Some((token.text.clone(), token.start, token.end));
+                    }
+                }
+            }
+            if let Some((text, start, end)) = current {
+                words.push(TimedWord {
+                    key: word_key(&text),
+                    start,
+                    end,
+                });
+            }
+        } else {
+            let parts: Vec<&str> = segment.text.split_whitespace().collect();
+            let total: usize = parts.iter().map(|p| p.chars().count()).sum();
+            let duration = (segment.end - segment.start).max(0);
+            let mut chars_before = 0;
+            for part in parts {
+                let len = part.chars().count();
+                let at = |chars: usize| segment.start + duration * chars as i64 / total.max(1) as i64;
+                words.push(TimedWord {
+                    key: word_key(part),
+                    start: at(chars_before),
+                    end: at(chars_before + len),
+                });
+                chars_before += len;
+            }
+        }
+    }
+    words.retain(|w| !w.key.is_empty());
+    words
+}
+
+/// Give each cue the times of the words it matches in `transcript`, keeping its text.
+/// Cues are searched for in order; one whose words can't be found moves along with the
+/// last cue that was, keeping its original length.
+pub fn retime_cues(cues: &[Segment], transcript: &[Segment]) -> Vec<Segment> {
+    let words = timed_words(transcript);
+    let mut cursor = 0;
+    let mut matched: Vec<Option<(i64, i64)>> = Vec::with_capacity(cues.len());
+    for cue in cues {
+        let keys: Vec<String> = cue
+            .text
+            .split_whitespace()
+            .map(word_key)
+            .filter(|k| !k.is_empty())
+            .collect();
+        let window = &words[cursor..(cursor + keys.len() + SEARCH_WINDOW_WORDS).min(words.len())];
+        match align_cue(&keys, window) {
+            Some((first, last)) => {
+                matched.push(Some((window[first].start, window[last].end)));
+                cursor += last + 1;
+            }
+            None => matched.push(None),
+        }
+    }
+
+    let mut retimed = Vec::with_capacity(cues.len());
+    let mut shift = 0;
+    for (idx, (cue, times)) in cues.iter().zip(&matched).enumerate() {
+        let (start, end) = match *times {
+            Some((start, end)) => {
+                shift = start - cue.start;
+                (start, end.max(start))
+            }
+            None => {
+                // Don't run into the next cue that was found
+                let limit = matched[idx + 1..]
+                    .iter()
+                    .flatten()
+                    .next()
+                    .map(|&(start, _)| start);
+                let start = (cue.start + shift).max(0);
+                let end = (cue.end + shift).max(start);
+                match limit {
+                    Some(limit) => (start.min(limit), end.min(limit)),
+                    None => (start, end),
+                }
+            }
+        };
+        retimed.push(Segment {
+            start,
+            end,
+            ..cue.clone()
+        });
+    }
+    retimed
+}
+
+/// Local (Smith-Waterman) alignment of a cue's words against a stretch of recognized words.
+/// Returns the first and last matched positions in `window` when enough of the cue was found.
+fn align_cue(keys: &[String], window: &[TimedWord]) -> Option<(usize, usize)> {
+    if keys.is_empty() || window.is_empty() {
+        return None;
+    }
+    let cols = window.len() + 1;
+    let mut score = vec![0i32; (keys.len() + 1) * cols];
+    let mut best = (0, 0, 0);
+    for i in 1..=keys.len() {
+        for j in 1..cols {
+            let diagonal = score[(i - 1) * cols + j - 1]
+                + if keys[i - 1] == window[j - 1].key {
+                    MATCH_SCORE
+                } else {
+                    MISMATCH_SCORE
+                };
+            let value = diagonal
+                .max(score[(i - 1) * cols + j] + GAP_SCORE)
+                .max(score[i * cols + j - 1] + GAP_SCORE)
+                .max(0);
+            score[i * cols + j] = value;
+            if value > best.0 {
+                best = (value, i, j);
+            }
+        }
+    }
+
+    // Trace back to where the best local match starts, counting the shared words
+    let (best_score, mut i, mut j) = best;
+    if best_score == 0 {
+        return None;
+    }
+    // The best cell always holds a matched pair
+    let (last_key, last) = (i - 1, j - 1);
+    let (mut first_key, mut first) = (last_key, last);
+    let mut matches = 0;
+    while i > 0 && j > 0 && score[i * cols + j] > 0 {
+        let here = score[i * cols + j];
+        let same = keys[i - 1] == window[j - 1].key;
+        let diagonal = score[(i - 1) * cols + j - 1] + if same { MATCH_SCORE } else { MISMATCH_SCORE };
+        if here == diagonal {
+            if same {
+                matches += 1;
+                (first_key, first) = (i - 1, j - 1);
+            }
+            i -= 1;
+            j -= 1;
+        } else if here == score[(i - 1) * cols + j] + GAP_SCORE {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    // At least a third of the cue's words, so a lone common word doesn't pin it down
+    if matches * 3 < keys.len() {
+        return None;
+    }
+    // Cover misheard words at either end of the cue too
+    let first = first.saturating_sub(first_key);
+    let last = (last + keys.len() - 1 - last_key).min(window.len() - 1);
+    Some((first, last))
+}
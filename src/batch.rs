@@ -0,0 +1,279 @@
+//! Queue of audio files transcribed one after another with the same model and settings
+
+use crate::i18n::{UiLanguage, tr};
+use crate::{Segment, probe_duration_secs};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// One file in the batch queue
+pub struct BatchItem {
+    pub path: PathBuf,
+    pub status: BatchStatus,
+    // Language whisper detected for this file, for output names and text cleanup
+    pub detected_language: Option<String>,
+}
+
+impl BatchItem {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            status: BatchStatus::Pending,
+            detected_language: None,
+        }
+    }
+
+    /// File name shown in the queue and used as the heading in combined documents
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+pub enum BatchStatus {
+    Pending,
+    Running,
+    Done(Vec<Segment>),
+    Failed(String),
+    Cancelled,
+}
+
+/// State of a running batch, written after every file so one cut short by a crash or by
+/// closing the app can pick up where it stopped
+#[derive(Serialize, Deserialize)]
+pub struct BatchProgress {
+    pub output_dir: PathBuf,
+    pub items: Vec<SavedBatchItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedBatchItem {
+    pub path: PathBuf,
+    pub detected_language: Option<String>,
+    // The transcript once the file is done, kept for the combined document; None still to do
+    pub segments: Option<Vec<Segment>>,
+}
+
+impl BatchProgress {
+    pub fn new(items: &[BatchItem], output_dir: &Path) -> Self {
+        Self {
+            output_dir: output_dir.to_path_buf(),
+            items: items
+                .iter()
+                .map(|item| SavedBatchItem {
+                    path: item.path.clone(),
+                    detected_language: item.detected_language.clone(),
+                    segments: match item.status {
+                        BatchStatus::Done(ref segments) => Some(segments.clone()),
+                        _ => None,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    /// Files not finished yet; failed and cancelled ones are tried again
+    pub fn remaining(&self) -> usize {
+        self.items.iter().filter(|item| item.segments.is_none()).count()
+    }
+
+    /// The queue to carry on with: finished files stay done, the rest wait their turn
+    pub fn into_queue(self) -> Vec<BatchItem> {
+        self.items
+            .into_iter()
+            .map(|item| BatchItem {
+                path: item.path,
+                status: match item.segments {
+                    Some(segments) => BatchStatus::Done(segments),
+                    None => BatchStatus::Pending,
+                },
+                detected_language: item.detected_language,
+            })
+            .collect()
+    }
+
+    pub fn load(path: &Path) -> Option<Self> {
+        serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+    }
+
+    /// Write through a temp file and rename, so a crash mid-write leaves the previous state
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create the settings folder")?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?).context("Failed to write batch progress")?;
+        std::fs::rename(&tmp, path).context("Failed to write batch progress")?;
+        Ok(())
+    }
+}
+
+/// Ways to cut a large queue down to a representative subset for spot checks
+#[derive(Clone, Copy, PartialEq)]
+pub enum BatchSample {
+    EveryNth(usize),
+    RandomPercent(u32),
+}
+
+impl BatchSample {
+    pub fn label(self) -> &'static str {
+        match self {
+            BatchSample::EveryNth(_) => "Every Nth file",
+            BatchSample::RandomPercent(_) => "Random percentage",
+        }
+    }
+
+    /// Short description for the note left after sampling, e.g. "Every Nth file: 10"
+    pub fn describe(self, lang: UiLanguage) -> String {
+        match self {
+            BatchSample::EveryNth(n) => format!("{}: {}", tr(lang, self.label()), n),
+            BatchSample::RandomPercent(percent) => format!("{}: {}%", tr(lang, self.label()), percent),
+        }
+    }
+
+    /// Positions to keep out of `count` files, in queue order. A random sample keeps at
+    /// least one file, chosen with a xorshift generator started from `seed`.
+    pub fn pick(self, count: usize, seed: u64) -> Vec<usize> {
+        match self {
+            BatchSample::EveryNth(n) => (0..count).step_by(n.max(1)).collect(),
+            BatchSample::RandomPercent(percent) => {
+                let keep = ((count * percent.min(100) as usize + 50) / 100).clamp(1, count.max(1));
+                let mut order: Vec<usize> = (0..count).collect();
+                let mut state = seed | 1;
+                // Partial Fisher-Yates: the first `keep` slots end up a uniform random pick
+                for i in 0..keep.min(count) {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    let j = i + (state % (count - i) as u64) as usize;
+                    order.swap(i, j);
+                }
+                order.truncate(keep.min(count));
+                order.sort_unstable();
+                order
+            }
+        }
+    }
+}
+
+/// What a batch is about to do, shown for confirmation before it starts
+pub struct BatchEstimate {
+    pub files: usize,
+    pub audio_secs: f64,
+    // Files whose length isn't in their headers, left out of audio_secs
+    pub unknown: usize,
+}
+
+impl BatchEstimate {
+    /// Add up the lengths of the files from their headers
+    pub fn probe(paths: &[PathBuf]) -> Self {
+        let mut estimate = Self {
+            files: paths.len(),
+            audio_secs: 0.0,
+            unknown: 0,
+        };
+        for path in paths {
+            match probe_duration_secs(path) {
+                Some(secs) => estimate.audio_secs += secs,
+                None => estimate.unknown += 1,
+            }
+        }
+        estimate
+    }
+}
+
+/// Rough human-readable length, e.g. "2 h 05 min" or "40 s"
+pub fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    if secs >= 3600 {
+        format!("{} h {:02} min", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{} min", (secs + 30) / 60)
+    } else {
+        format!("{} s", secs)
+    }
+}
+
+/// Layout of the single document that collects every transcript in a batch
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CombinedFormat {
+    Text,
+    Markdown,
+}
+
+impl CombinedFormat {
+    pub const ALL: [CombinedFormat; 2] = [CombinedFormat::Text, CombinedFormat::Markdown];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CombinedFormat::Text => "Text (.txt)",
+            CombinedFormat::Markdown => "Markdown (.md)",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            CombinedFormat::Text => "txt",
+            CombinedFormat::Markdown => "md",
+        }
+    }
+}
+
+/// One document with a heading per file followed by its transcript, or the reason it failed
+pub fn combined_document(entries: &[(String, Result<String, String>)], format: CombinedFormat) -> String {
+    let mut doc = String::new();
+    for (name, transcript) in entries {
+        match format {
+            CombinedFormat::Text => {
+                doc.push_str(name);
+                doc.push('\n');
+                doc.push_str(&"=".repeat(name.chars().count()));
+                doc.push_str("\n\n");
+            }
+            CombinedFormat::Markdown => {
+                doc.push_str(&format!("## {}\n\n", name));
+            }
+        }
+        match (transcript, format) {
+            (Ok(text), _) => doc.push_str(text),
+            (Err(e), CombinedFormat::Text) => doc.push_str(&format!("(failed: {})", e)),
+            (Err(e), CombinedFormat::Markdown) => doc.push_str(&format!("*Failed: {}*", e)),
+        }
+        doc.push_str("\n\n");
+    }
+    doc.truncate(doc.trim_end().len());
+    doc.push('\n');
+    doc
+}
+
+/// CSV listing every failed file with its full path and the error, or None when nothing failed
+pub fn failure_report(items: &[BatchItem]) -> Option<String> {
+    let failures: Vec<(&BatchItem, &String)> = items
+        .iter()
+        .filter_map(|item| match item.status {
+            BatchStatus::Failed(ref e) => Some((item, e)),
+            _ => None,
+        })
+        .collect();
+    if failures.is_empty() {
+        return None;
+    }
+
+    let mut csv = String::from("file,path,reason\n");
+    for (item, reason) in failures {
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            csv_field(&item.name()),
+            csv_field(&item.path.display().to_string()),
+            csv_field(reason)
+        ));
+    }
+    Some(csv)
+}
+
+/// Quote a CSV field, doubling any quotes inside it
+fn csv_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
@@ -0,0 +1,369 @@
+//! Interface translations, keyed by the English UI text
+
+use serde::{Deserialize, Serialize};
+
+/// Language used for the app's own labels (not the spoken audio language)
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum UiLanguage {
+    #[default]
+    English,
+    Spanish,
+    German,
+}
+
+impl UiLanguage {
+    pub const ALL: [UiLanguage; 3] = [UiLanguage::English, UiLanguage::Spanish, UiLanguage::German];
+
+    /// Name of the language in that language, for the selector
+    pub fn label(self) -> &'static str {
+        match self {
+            UiLanguage::English => "English",
+            UiLanguage::Spanish => "Español",
+            UiLanguage::German => "Deutsch",
+        }
+    }
+}
+
+/// Translate a UI string, falling back to the English key when there's no entry
+pub fn tr(lang: UiLanguage, key: &'static str) -> &'static str {
+    let column = match lang {
+        UiLanguage::English => return key,
+        UiLanguage::Spanish => 0,
+        UiLanguage::German => 1,
+    };
+    TRANSLATIONS
+        .iter()
+        .find(|(english, _)| *english == key)
+        .map_or(key, |(_, translated)| translated[column])
+}
+
+// English key, then [Spanish, German]
+const TRANSLATIONS: &[(&str, [&str; 2])] = &[
+    ("Download Model:", ["Descargar modelo:", "Modell herunterladen:"]),
+    ("Checking the downloaded model loads...", ["Comprobando que el modelo descargado se carga...", "Prüfe, ob das heruntergeladene Modell lädt..."]),
+    ("Model:", ["Modelo:", "Modell:"]),
+    ("(none)", ["(ninguno)", "(keins)"]),
+    ("Audio:", ["Audio:", "Audio:"]),
+    ("Profile:", ["Perfil:", "Profil:"]),
+    ("Language:", ["Idioma:", "Sprache:"]),
+    ("Drag & drop audio files (MP3, WAV, FLAC, OGG, M4A, AAC, WMA, Opus)", ["Arrastra y suelta archivos de audio (MP3, WAV, FLAC, OGG, M4A, AAC, WMA, Opus)", "Audiodateien per Drag & Drop ablegen (MP3, WAV, FLAC, OGG, M4A, AAC, WMA, Opus)"]),
+    ("Theme:", ["Tema:", "Design:"]),
+    ("Interface language:", ["Idioma de la interfaz:", "Oberflächensprache:"]),
+    ("Models folder:", ["Carpeta de modelos:", "Modellordner:"]),
+    ("Download proxy:", ["Proxy de descarga:", "Download-Proxy:"]),
+    ("Proxy user:", ["Usuario del proxy:", "Proxy-Benutzer:"]),
+    ("Password:", ["Contraseña:", "Passwort:"]),
+    ("Initial prompt:", ["Prompt inicial:", "Start-Prompt:"]),
+    ("Best of:", ["Mejor de:", "Beste aus:"]),
+    ("Temperature:", ["Temperatura:", "Temperatur:"]),
+    ("Fallback increment:", ["Incremento de reintento:", "Fallback-Schritt:"]),
+    ("Resampler window:", ["Ventana del remuestreo:", "Resampler-Fenster:"]),
+    ("Cutoff:", ["Corte:", "Grenzfrequenz:"]),
+    ("Oversampling:", ["Sobremuestreo:", "Oversampling:"]),
+    ("Min duration (s):", ["Duración mínima (s):", "Mindestdauer (s):"]),
+    ("Max chars:", ["Máx. caracteres:", "Max. Zeichen:"]),
+    ("Loading...", ["Cargando...", "Lädt..."]),
+    ("Transcribing...", ["Transcribiendo...", "Transkribiere..."]),
+    ("Done!", ["¡Listo!", "Fertig!"]),
+    ("Transcription:", ["Transcripción:", "Transkription:"]),
+    ("Download", ["Descargar", "Herunterladen"]),
+    ("Save", ["Guardar", "Speichern"]),
+    ("Reset", ["Restablecer", "Zurücksetzen"]),
+    ("Run benchmark", ["Ejecutar prueba", "Benchmark starten"]),
+    ("Transcribe", ["Transcribir", "Transkribieren"]),
+    ("Copy", ["Copiar", "Kopieren"]),
+    ("Save...", ["Guardar...", "Speichern..."]),
+    ("Export all...", ["Exportar todo...", "Alles exportieren..."]),
+    ("Re-download", ["Volver a descargar", "Erneut herunterladen"]),
+    ("Browse...", ["Examinar...", "Durchsuchen..."]),
+    ("Paste", ["Pegar", "Einfügen"]),
+    ("Delete", ["Eliminar", "Löschen"]),
+    ("Change...", ["Cambiar...", "Ändern..."]),
+    ("Cancel", ["Cancelar", "Abbrechen"]),
+    ("Clear", ["Borrar", "Leeren"]),
+    ("Abort all", ["Abortar todo", "Alles abbrechen"]),
+    ("Advanced", ["Avanzado", "Erweitert"]),
+    ("Benchmark", ["Prueba de rendimiento", "Benchmark"]),
+    ("New profile name", ["Nombre del nuevo perfil", "Name des neuen Profils"]),
+    ("http://proxy:8080 (default: system env)", ["http://proxy:8080 (por defecto: entorno del sistema)", "http://proxy:8080 (Standard: Systemumgebung)"]),
+    ("Names, jargon or style to prime the model", ["Nombres, jerga o estilo para orientar al modelo", "Namen, Fachbegriffe oder Stil als Vorgabe für das Modell"]),
+    ("Notify when transcription finishes", ["Avisar cuando termine la transcripción", "Benachrichtigen, wenn die Transkription fertig ist"]),
+    ("Use GPU when available", ["Usar la GPU si está disponible", "GPU verwenden, wenn verfügbar"]),
+    ("Limit text context (tokens):", ["Limitar contexto de texto (tokens):", "Textkontext begrenzen (Tokens):"]),
+    ("Threads:", ["Hilos:", "Threads:"]),
+    ("Skip silent audio below (dBFS):", ["Omitir audio silencioso por debajo de (dBFS):", "Stilles Audio überspringen unter (dBFS):"]),
+    ("Precise word timestamps (DTW)", ["Marcas de tiempo precisas por palabra (DTW)", "Genaue Wort-Zeitstempel (DTW)"]),
+    ("Force single segment", ["Forzar un único segmento", "Einzelnes Segment erzwingen"]),
+    ("Condition on previous text", ["Condicionar al texto anterior", "Vorherigen Text als Kontext nutzen"]),
+    ("Confidence threshold:", ["Umbral de confianza:", "Konfidenzschwelle:"]),
+    ("Mark with [?] instead of dropping", ["Marcar con [?] en lugar de omitir", "Mit [?] markieren statt weglassen"]),
+    ("Merge short subtitle segments", ["Unir segmentos de subtítulos cortos", "Kurze Untertitelsegmente zusammenführen"]),
+    ("Transcribe when download finishes", ["Transcribir al terminar la descarga", "Nach dem Download transkribieren"]),
+    ("Use an audio file copied in the file manager", ["Usar un archivo de audio copiado en el explorador de archivos", "Eine im Dateimanager kopierte Audiodatei verwenden"]),
+    ("Lower values use less memory; higher values keep more context on long audio", ["Valores bajos usan menos memoria; valores altos conservan más contexto en audios largos", "Niedrigere Werte sparen Speicher; höhere behalten bei langem Audio mehr Kontext"]),
+    ("Higher values are more robust but slower", ["Valores altos son más robustos pero más lentos", "Höhere Werte sind robuster, aber langsamer"]),
+    ("Align tokens to the audio so subtitles start on the right word; works with the standard tiny–large models", ["Alinea los tokens con el audio para que los subtítulos empiecen en la palabra correcta; funciona con los modelos estándar tiny–large", "Richtet Tokens am Audio aus, damit Untertitel beim richtigen Wort beginnen; funktioniert mit den Standardmodellen tiny–large"]),
+    ("Emit one segment for the whole clip; useful for short clips", ["Genera un solo segmento para todo el clip; útil para clips cortos", "Ein Segment für den ganzen Clip; nützlich für kurze Clips"]),
+    ("Feed earlier text back as context; can loop on music or ASMR", ["Usa el texto anterior como contexto; puede repetirse con música o ASMR", "Früheren Text als Kontext einspeisen; kann bei Musik oder ASMR in Schleifen geraten"]),
+    ("Leave out segments whisper was unsure about", ["Omite los segmentos en los que whisper no estaba seguro", "Segmente weglassen, bei denen whisper unsicher war"]),
+    ("Write .txt, .srt, .vtt, .json and .jsonl into a folder", ["Escribe .txt, .srt, .vtt, .json y .jsonl en una carpeta", "Schreibt .txt, .srt, .vtt, .json und .jsonl in einen Ordner"]),
+    ("Running on GPU", ["Ejecutando en GPU", "Läuft auf GPU"]),
+    ("Running on CPU", ["Ejecutando en CPU", "Läuft auf CPU"]),
+    ("Ready", ["Listo", "Bereit"]),
+    ("Selection:", ["Selección:", "Auswahl:"]),
+    ("Clear selection", ["Quitar selección", "Auswahl aufheben"]),
+    (
+        "Drag across the waveform to transcribe only part of it",
+        [
+            "Arrastra sobre la forma de onda para transcribir solo una parte",
+            "Über die Wellenform ziehen, um nur einen Teil zu transkribieren",
+        ],
+    ),
+    ("Max subtitle duration (s):", ["Duración máx. del subtítulo (s):", "Max. Untertiteldauer (s):"]),
+    (
+        "Split longer subtitles at word boundaries",
+        ["Divide los subtítulos más largos entre palabras", "Längere Untertitel an Wortgrenzen teilen"],
+    ),
+    ("Copied!", ["¡Copiado!", "Kopiert!"]),
+    ("Clipboard unavailable", ["Portapapeles no disponible", "Zwischenablage nicht verfügbar"]),
+    ("Memory-map audio files", ["Mapear archivos de audio en memoria", "Audiodateien per Memory-Mapping lesen"]),
+    (
+        "Can speed up reading very large files",
+        ["Puede acelerar la lectura de archivos muy grandes", "Kann das Lesen sehr großer Dateien beschleunigen"],
+    ),
+    ("Clean up numbers and punctuation", ["Limpiar números y puntuación", "Zahlen und Satzzeichen bereinigen"]),
+    (
+        "Write spelled-out numbers as digits (English) and fix spacing around punctuation",
+        [
+            "Escribe los números deletreados como cifras (inglés) y corrige los espacios junto a la puntuación",
+            "Ausgeschriebene Zahlen als Ziffern schreiben (Englisch) und Leerzeichen an Satzzeichen korrigieren",
+        ],
+    ),
+    ("Output name:", ["Nombre de salida:", "Ausgabename:"]),
+    (
+        "Used by Export all; tokens: {stem} {model} {lang} {ext}",
+        [
+            "Lo usa Exportar todo; variables: {stem} {model} {lang} {ext}",
+            "Wird von Alles exportieren verwendet; Platzhalter: {stem} {model} {lang} {ext}",
+        ],
+    ),
+    ("After saving, run:", ["Tras guardar, ejecutar:", "Nach dem Speichern ausführen:"]),
+    (
+        "Runs a shell command with {file} replaced by the saved transcript's path",
+        [
+            "Ejecuta un comando del sistema sustituyendo {file} por la ruta de la transcripción guardada",
+            "Führt einen Shell-Befehl aus, wobei {file} durch den Pfad der gespeicherten Transkription ersetzt wird",
+        ],
+    ),
+    ("Undo clear", ["Deshacer borrado", "Leeren rückgängig"]),
+    ("Verifying on CPU...", ["Verificando en CPU...", "Überprüfung auf der CPU..."]),
+    ("Verify GPU output on CPU", ["Verificar el resultado de la GPU en la CPU", "GPU-Ergebnis auf der CPU überprüfen"]),
+    (
+        "Transcribes twice and flags segments where the two runs differ",
+        [
+            "Transcribe dos veces y marca los segmentos en los que ambas ejecuciones difieren",
+            "Transkribiert zweimal und markiert Segmente, in denen sich die beiden Durchläufe unterscheiden",
+        ],
+    ),
+    ("GPU and CPU transcripts match", ["Las transcripciones de GPU y CPU coinciden", "GPU- und CPU-Transkript stimmen überein"]),
+    (
+        "Segments that differ between GPU and CPU",
+        ["Segmentos que difieren entre GPU y CPU", "Segmente, die sich zwischen GPU und CPU unterscheiden"],
+    ),
+    ("Time", ["Tiempo", "Zeit"]),
+    ("Mark speaker turns", ["Marcar cambios de hablante", "Sprecherwechsel markieren"]),
+    ("Start a new line when the speaker changes", ["Empieza una nueva línea cuando cambia el hablante", "Beginnt eine neue Zeile, wenn der Sprecher wechselt"]),
+    ("Needs a tinydiarize model, e.g. ggml-small.en-tdrz.bin", ["Requiere un modelo tinydiarize, p. ej. ggml-small.en-tdrz.bin", "Benötigt ein tinydiarize-Modell, z. B. ggml-small.en-tdrz.bin"]),
+    ("(unchanged, not decoded again)", ["(sin cambios, no se decodifica de nuevo)", "(unverändert, nicht erneut dekodiert)"]),
+    ("Maximize output", ["Maximizar resultado", "Ausgabe maximieren"]),
+    ("Show all controls", ["Mostrar todos los controles", "Alle Bedienelemente anzeigen"]),
+    ("Auto-detect", ["Detección automática", "Automatisch erkennen"]),
+    ("Skip timestamps (text only)", ["Omitir marcas de tiempo (solo texto)", "Zeitstempel überspringen (nur Text)"]),
+    ("Faster when you only need the text; disables SRT and VTT export", ["Más rápido si solo necesitas el texto; desactiva la exportación SRT y VTT", "Schneller, wenn nur der Text gebraucht wird; deaktiviert den SRT- und VTT-Export"]),
+    ("Write .txt, .json and .jsonl into a folder", ["Escribe .txt, .json y .jsonl en una carpeta", "Schreibt .txt, .json und .jsonl in einen Ordner"]),
+    ("Show special tokens", ["Mostrar tokens especiales", "Sondertokens anzeigen"]),
+    ("Keep whisper's raw token markers in the output; useful when tuning prompts", ["Conserva los marcadores de tokens de whisper en el resultado; útil al ajustar prompts", "Behält whispers rohe Token-Markierungen in der Ausgabe; nützlich beim Abstimmen von Prompts"]),
+    ("Batch", ["Lote", "Stapel"]),
+    ("Transcribes each queued file in turn with the current model and settings.", ["Transcribe cada archivo de la cola por turnos con el modelo y los ajustes actuales.", "Transkribiert jede Datei in der Warteschlange nacheinander mit dem aktuellen Modell und den aktuellen Einstellungen."]),
+    ("Add files...", ["Añadir archivos...", "Dateien hinzufügen..."]),
+    ("Clear list", ["Vaciar lista", "Liste leeren"]),
+    ("Save each transcript as", ["Guardar cada transcripción como", "Jedes Transkript speichern als"]),
+    ("Combine all transcripts into one document", ["Combinar todas las transcripciones en un documento", "Alle Transkripte in einem Dokument zusammenfassen"]),
+    ("Waiting", ["En espera", "Wartet"]),
+    ("Done", ["Hecho", "Fertig"]),
+    ("Failed", ["Error", "Fehlgeschlagen"]),
+    ("Cancelled", ["Cancelado", "Abgebrochen"]),
+    ("Start batch...", ["Iniciar lote...", "Stapel starten..."]),
+    ("Choose a folder for the transcripts, then start", ["Elige una carpeta para las transcripciones y empieza", "Ordner für die Transkripte wählen und starten"]),
+    ("Stop", ["Detener", "Stoppen"]),
+    ("Remove from queue", ["Quitar de la cola", "Aus der Warteschlange entfernen"]),
+    ("Drag to reorder", ["Arrastra para reordenar", "Zum Umsortieren ziehen"]),
+    ("Remove made-up closing phrases", ["Quitar frases finales inventadas", "Erfundene Schlusssätze entfernen"]),
+    ("Drops phrases like \"Thanks for watching!\" when whisper adds them, unsure, at the very end", ["Elimina frases como \"¡Gracias por ver!\" cuando whisper las añade con poca seguridad al final", "Entfernt Sätze wie \"Danke fürs Zuschauen!\", wenn whisper sie unsicher ganz am Ende anhängt"]),
+    ("Edit list...", ["Editar lista...", "Liste bearbeiten..."]),
+    ("One phrase per line; case and punctuation are ignored", ["Una frase por línea; se ignoran mayúsculas y puntuación", "Ein Satz pro Zeile; Groß-/Kleinschreibung und Satzzeichen werden ignoriert"]),
+    ("Downloading from", ["Descargando desde", "Herunterladen von"]),
+    ("(source audio deleted)", ["(audio original eliminado)", "(Quellaudio gelöscht)"]),
+    ("Delete audio after transcription?", ["¿Eliminar el audio después de transcribir?", "Audio nach der Transkription löschen?"]),
+    (
+        "Each audio file will be permanently deleted once its transcript has been saved. \
+         Files are never deleted when transcription fails or is cancelled.",
+        [
+            "Cada archivo de audio se eliminará permanentemente cuando se haya guardado su transcripción. \
+             Los archivos nunca se eliminan si la transcripción falla o se cancela.",
+            "Jede Audiodatei wird endgültig gelöscht, sobald ihr Transkript gespeichert ist. \
+             Bei fehlgeschlagener oder abgebrochener Transkription wird nichts gelöscht.",
+        ],
+    ),
+    ("Delete after saving", ["Eliminar tras guardar", "Nach dem Speichern löschen"]),
+    ("Delete audio after successful transcription", ["Eliminar el audio tras una transcripción correcta", "Audio nach erfolgreicher Transkription löschen"]),
+    ("Removes the source file once its transcript is saved", ["Elimina el archivo original cuando se guarda su transcripción", "Entfernt die Quelldatei, sobald ihr Transkript gespeichert ist"]),
+    ("Start batch?", ["¿Iniciar el lote?", "Stapel starten?"]),
+    ("Checking the files...", ["Comprobando los archivos...", "Dateien werden geprüft..."]),
+    ("Files", ["Archivos", "Dateien"]),
+    ("of audio", ["de audio", "Audio"]),
+    ("files don't record their length and aren't counted", ["archivos no indican su duración y no se cuentan", "Dateien geben ihre Länge nicht an und werden nicht mitgezählt"]),
+    ("Estimated time:", ["Tiempo estimado:", "Geschätzte Dauer:"]),
+    ("No speed measured for this model yet, so there's no time estimate", ["Aún no se ha medido la velocidad de este modelo, así que no hay estimación de tiempo", "Für dieses Modell wurde noch keine Geschwindigkeit gemessen, daher gibt es keine Zeitschätzung"]),
+    ("Start", ["Iniciar", "Starten"]),
+    ("Line endings:", ["Fin de línea:", "Zeilenenden:"]),
+    ("System default", ["Predeterminado del sistema", "Systemstandard"]),
+    ("Used for saved .txt, .srt and .vtt files", ["Se usa en los archivos .txt, .srt y .vtt guardados", "Gilt für gespeicherte .txt-, .srt- und .vtt-Dateien"]),
+    ("Transcribe again with", ["Transcribir de nuevo con", "Erneut transkribieren mit"]),
+    ("Same audio and settings; downloads the model first if needed", ["Mismo audio y ajustes; descarga el modelo antes si hace falta", "Gleiches Audio und gleiche Einstellungen; lädt das Modell bei Bedarf zuerst herunter"]),
+    ("English-only model", ["Modelo solo en inglés", "Nur-Englisch-Modell"]),
+    ("The selected model only understands English.", ["El modelo seleccionado solo entiende inglés.", "Das gewählte Modell versteht nur Englisch."]),
+    ("Selected language:", ["Idioma seleccionado:", "Gewählte Sprache:"]),
+    ("Pick a multilingual model (without \".en\" in its name) for other languages.", ["Elige un modelo multilingüe (sin \".en\" en el nombre) para otros idiomas.", "Wähle für andere Sprachen ein mehrsprachiges Modell (ohne \".en\" im Namen)."]),
+    ("Transcribe anyway", ["Transcribir de todos modos", "Trotzdem transkribieren"]),
+    ("Auto gain:", ["Ganancia automática:", "Automatische Verstärkung:"]),
+    ("Lifts quiet passages, such as a speaker far from the mic, towards the level of loud ones. 0 turns it off.", ["Sube los pasajes bajos, como un hablante lejos del micrófono, hacia el nivel de los fuertes. 0 lo desactiva.", "Hebt leise Passagen, etwa bei Sprechern weit vom Mikrofon, auf den Pegel der lauten an. 0 schaltet es aus."]),
+    ("Correct the text segment by segment, playing each one back", ["Corrige el texto segmento a segmento, escuchando cada uno", "Text Segment für Segment korrigieren und jedes anhören"]),
+    ("Play this segment", ["Reproducir este segmento", "Dieses Segment abspielen"]),
+    ("Playback needs the audio preview to finish loading", ["La reproducción necesita que termine de cargarse la vista previa del audio", "Die Wiedergabe braucht die fertig geladene Audiovorschau"]),
+    ("No speech detected", ["No se detectó voz", "Keine Sprache erkannt"]),
+    ("Save chapters...", ["Guardar capítulos...", "Kapitel speichern..."]),
+    ("Chapter markers at pauses in the speech, as podcast JSON or FFmpeg metadata; the audio file isn't changed", ["Marcas de capítulo en las pausas del habla, como JSON de pódcast o metadatos de FFmpeg; el archivo de audio no se modifica", "Kapitelmarken an Sprechpausen, als Podcast-JSON oder FFmpeg-Metadaten; die Audiodatei bleibt unverändert"]),
+    ("New chapter after pauses of (s):", ["Nuevo capítulo tras pausas de (s):", "Neues Kapitel nach Pausen von (s):"]),
+    ("fastest, lowest accuracy", ["el más rápido, la menor precisión", "am schnellsten, geringste Genauigkeit"]),
+    ("fast, fair accuracy", ["rápido, precisión aceptable", "schnell, ordentliche Genauigkeit"]),
+    ("balanced speed and accuracy", ["equilibrio entre velocidad y precisión", "ausgewogen zwischen Tempo und Genauigkeit"]),
+    ("slow, high accuracy", ["lento, alta precisión", "langsam, hohe Genauigkeit"]),
+    ("best accuracy, slow without a GPU", ["la mejor precisión, lento sin GPU", "beste Genauigkeit, langsam ohne GPU"]),
+    ("Right-click to copy with the file name and length on top", ["Clic derecho para copiar con el nombre del archivo y la duración arriba", "Rechtsklick, um mit Dateiname und Länge darüber zu kopieren"]),
+    ("Copy with file name", ["Copiar con el nombre del archivo", "Mit Dateiname kopieren"]),
+    ("Text", ["Texto", "Text"]),
+    ("Segments", ["Segmentos", "Segmente"]),
+    ("Edit", ["Editar", "Bearbeiten"]),
+    ("One line per segment with its times; Tab or the arrow keys move between them and Ctrl+C copies one", ["Una línea por segmento con sus tiempos; Tab o las flechas pasan de uno a otro y Ctrl+C copia uno", "Eine Zeile pro Segment mit Zeiten; Tab oder die Pfeiltasten wechseln zwischen ihnen, Strg+C kopiert eines"]),
+    ("CA certificate:", ["Certificado de CA:", "CA-Zertifikat:"]),
+    ("system certificates only", ["solo certificados del sistema", "nur Systemzertifikate"]),
+    ("Also trust this certificate for downloads, e.g. a company proxy's that inspects TLS", ["Confiar también en este certificado para las descargas, p. ej. el de un proxy de empresa que inspecciona TLS", "Diesem Zertifikat bei Downloads zusätzlich vertrauen, z. B. dem eines Firmen-Proxys, der TLS prüft"]),
+    ("Edited; whisper heard:", ["Editado; whisper entendió:", "Bearbeitet; whisper hörte:"]),
+    ("Revert to whisper's text", ["Volver al texto de whisper", "Auf den Text von whisper zurücksetzen"]),
+    ("Fix capitalization", ["Corregir mayúsculas", "Großschreibung korrigieren"]),
+    ("Capital letters at the start of sentences, and \"I\" in English, for models that write in lowercase", ["Mayúsculas al inicio de cada frase, y \"I\" en inglés, para modelos que escriben en minúsculas", "Großbuchstaben am Satzanfang und \"I\" im Englischen, für Modelle, die klein schreiben"]),
+    ("Self-test", ["Autoprueba", "Selbsttest"]),
+    ("Runs a generated tone through decoding and resampling and loads the selected model on the CPU, to tell a problem with the app or model apart from one with an audio file.", ["Pasa un tono generado por la decodificación y el remuestreo y carga el modelo seleccionado en la CPU, para distinguir un problema de la aplicación o del modelo de uno del archivo de audio.", "Schickt einen erzeugten Ton durch Dekodierung und Resampling und lädt das gewählte Modell auf der CPU, um ein Problem mit App oder Modell von einem mit der Audiodatei zu unterscheiden."]),
+    ("Run self-test", ["Ejecutar autoprueba", "Selbsttest starten"]),
+    ("Decode", ["Decodificación", "Dekodierung"]),
+    ("Resample", ["Remuestreo", "Resampling"]),
+    ("Model", ["Modelo", "Modell"]),
+    ("Pass", ["Correcto", "Bestanden"]),
+    ("Fail", ["Error", "Fehlgeschlagen"]),
+    ("Export settings...", ["Exportar ajustes...", "Einstellungen exportieren..."]),
+    ("Import settings...", ["Importar ajustes...", "Einstellungen importieren..."]),
+    ("Share transcription settings with others as a file", ["Compartir los ajustes de transcripción como archivo", "Transkriptionseinstellungen als Datei weitergeben"]),
+    ("Transcribe this segment again with the current settings and replace its text", ["Transcribir de nuevo este segmento con los ajustes actuales y reemplazar su texto", "Dieses Segment mit den aktuellen Einstellungen neu transkribieren und seinen Text ersetzen"]),
+    ("Resume unfinished batch?", ["¿Reanudar el lote sin terminar?", "Unfertigen Stapel fortsetzen?"]),
+    ("Resume", ["Reanudar", "Fortsetzen"]),
+    ("Discard", ["Descartar", "Verwerfen"]),
+    ("JSON timestamps:", ["Marcas de tiempo JSON:", "JSON-Zeitstempel:"]),
+    ("Seconds (decimal)", ["Segundos (decimales)", "Sekunden (dezimal)"]),
+    ("Milliseconds", ["Milisegundos", "Millisekunden"]),
+    ("Centiseconds", ["Centésimas de segundo", "Hundertstelsekunden"]),
+    ("Unit of start and end times in .json and .jsonl files, named in each segment's \"time_unit\"", ["Unidad de los tiempos de inicio y fin en archivos .json y .jsonl, indicada en el \"time_unit\" de cada segmento", "Einheit der Start- und Endzeiten in .json- und .jsonl-Dateien, angegeben im \"time_unit\" jedes Segments"]),
+    ("Sample:", ["Muestra:", "Stichprobe:"]),
+    ("Every Nth file", ["Cada N-ésimo archivo", "Jede N-te Datei"]),
+    ("Random percentage", ["Porcentaje aleatorio", "Zufälliger Anteil"]),
+    ("Apply", ["Aplicar", "Anwenden"]),
+    ("Remove the waiting files outside the sample from the queue", ["Quitar de la cola los archivos en espera que no entren en la muestra", "Wartende Dateien außerhalb der Stichprobe aus der Warteschlange entfernen"]),
+    ("About", ["Acerca de", "Über"]),
+    ("Version:", ["Versión:", "Version:"]),
+    ("GPU backend:", ["Backend de GPU:", "GPU-Backend:"]),
+    ("Format supported", ["Formato compatible", "Format unterstützt"]),
+    ("None selected", ["Ninguno seleccionado", "Keins ausgewählt"]),
+    ("Copy details", ["Copiar detalles", "Details kopieren"]),
+    ("Lead-in silence:", ["Silencio inicial:", "Stille vorab:"]),
+    ("Silence added before the audio so whisper doesn't clip the first word of short clips. 0 adds none.", ["Silencio añadido antes del audio para que whisper no corte la primera palabra de los clips cortos. 0 no añade nada.", "Stille vor dem Audio, damit whisper bei kurzen Clips das erste Wort nicht abschneidet. 0 fügt keine hinzu."]),
+    ("Download Model", ["Descargar modelo", "Modell herunterladen"]),
+    ("Re-time subtitles...", ["Resincronizar subtítulos...", "Untertitel neu timen..."]),
+    ("Transcribe, then give the cues of an existing SRT file the new timing, keeping their text", ["Transcribe y luego aplica los nuevos tiempos a los subtítulos de un archivo SRT existente, conservando su texto", "Transkribiert und überträgt dann das neue Timing auf die Untertitel einer vorhandenen SRT-Datei, deren Text bleibt erhalten"]),
+    ("GPU load timeout:", ["Tiempo límite de carga en GPU:", "Zeitlimit für GPU-Laden:"]),
+    ("Falls back to CPU when the GPU takes longer than this to load the model, e.g. after a driver crash. 0 waits forever.", ["Pasa a la CPU cuando la GPU tarda más que esto en cargar el modelo, p. ej. tras un fallo del controlador. 0 espera indefinidamente.", "Wechselt zur CPU, wenn die GPU länger zum Laden des Modells braucht, z. B. nach einem Treiberabsturz. 0 wartet unbegrenzt."]),
+    ("Words per minute", ["Palabras por minuto", "Wörter pro Minute"]),
+    ("Speaking rate of each segment, to spot rushed or slow passages", ["Velocidad de habla de cada segmento, para detectar pasajes apresurados o lentos", "Sprechtempo jedes Segments, um gehetzte oder langsame Passagen zu erkennen"]),
+    ("words per minute", ["palabras por minuto", "Wörter pro Minute"]),
+    ("Words spoken divided by the time inside segments", ["Palabras dichas divididas por el tiempo dentro de los segmentos", "Gesprochene Wörter geteilt durch die Zeit innerhalb der Segmente"]),
+    ("checked", ["marcados", "markiert"]),
+    ("Copy checked", ["Copiar marcados", "Markierte kopieren"]),
+    ("Save checked...", ["Guardar marcados...", "Markierte speichern..."]),
+    ("Uncheck all", ["Desmarcar todos", "Alle abwählen"]),
+    ("Show debug info", ["Mostrar información de depuración", "Debug-Infos anzeigen"]),
+    ("Shows each segment's no-speech probability in the Segments view", ["Muestra la probabilidad de ausencia de voz de cada segmento en la vista Segmentos", "Zeigt in der Segmentansicht für jedes Segment die Wahrscheinlichkeit, dass keine Sprache vorliegt"]),
+    ("Whisper's estimate that this part held no speech; high values on real text hint at a made-up segment", ["Estimación de Whisper de que esta parte no contenía voz; un valor alto con texto sugiere un segmento inventado", "Whispers Schätzung, dass dieser Teil keine Sprache enthielt; ein hoher Wert bei vorhandenem Text deutet auf ein erfundenes Segment hin"]),
+    ("Cut out", ["Recortar", "Herausschneiden"]),
+    ("Leave this range out of the transcription, e.g. an ad break", ["Excluye este tramo de la transcripción, p. ej. una pausa publicitaria", "Diesen Bereich nicht transkribieren, z. B. eine Werbepause"]),
+    ("cut out", ["recortados", "herausgeschnitten"]),
+    ("Restore cuts", ["Restaurar recortes", "Schnitte zurücknehmen"]),
+    ("Times on the original audio", ["Tiempos del audio original", "Zeiten der Originalaufnahme"]),
+    ("Off: times follow the audio with the cuts taken out. On: times match the source file.", ["Desactivado: los tiempos siguen el audio sin los recortes. Activado: coinciden con el archivo original.", "Aus: Zeiten folgen dem Audio ohne die Schnitte. An: Zeiten passen zur Quelldatei."]),
+    ("Also transcribe with:", ["Transcribir también con:", "Zusätzlich transkribieren mit:"]),
+    ("None", ["Ninguno", "Keins"]),
+    ("Transcribes a second time with this model and keeps whichever segments whisper was more confident of. Takes the time of both runs.", ["Transcribe una segunda vez con este modelo y conserva los segmentos de los que Whisper estaba más seguro. Tarda lo que ambas pasadas.", "Transkribiert ein zweites Mal mit diesem Modell und behält jeweils die Segmente, bei denen Whisper sicherer war. Dauert so lange wie beide Durchläufe."]),
+    ("Temp folder:", ["Carpeta temporal:", "Temp-Ordner:"]),
+    ("system default", ["predeterminada del sistema", "Systemstandard"]),
+    ("Downloads are staged here before they're moved into the models folder", ["Las descargas se guardan aquí antes de moverlas a la carpeta de modelos", "Downloads landen hier, bevor sie in den Modellordner verschoben werden"]),
+    ("Default", ["Predeterminada", "Standard"]),
+    ("Audio filter:", ["Filtro de audio:", "Audiofilter:"]),
+    ("Remove 60Hz hum", ["Quitar zumbido de 60 Hz", "60-Hz-Brummen entfernen"]),
+    ("Telephone band-pass for call recordings", ["Paso banda telefónico para grabaciones de llamadas", "Telefon-Bandpass für Anrufaufnahmen"]),
+    ("Reduce hiss", ["Reducir siseo", "Rauschen verringern"]),
+    ("Filters the audio before transcribing to take out mains hum, keep only the voice band of a phone call, or soften hiss", ["Filtra el audio antes de transcribir para quitar el zumbido de la red eléctrica, dejar solo la banda de voz de una llamada o suavizar el siseo", "Filtert das Audio vor dem Transkribieren, um Netzbrummen zu entfernen, nur das Sprachband eines Anrufs zu behalten oder Rauschen abzuschwächen"]),
+    ("Whisper Transcribe", ["Whisper Transcribe", "Whisper Transcribe"]),
+    ("Welcome to Whisper Transcribe", ["Bienvenido a Whisper Transcribe", "Willkommen bei Whisper Transcribe"]),
+    ("Pick a model to download. Larger models are more accurate but slower.", ["Elige un modelo para descargar. Los modelos más grandes son más precisos pero más lentos.", "Wähle ein Modell zum Herunterladen. Größere Modelle sind genauer, aber langsamer."]),
+    ("Recommended for this machine:", ["Recomendado para este equipo:", "Empfohlen für diesen Rechner:"]),
+    ("No usable GPU found; CPU will be used.", ["No se encontró una GPU utilizable; se usará la CPU.", "Keine nutzbare GPU gefunden; die CPU wird verwendet."]),
+    ("Download and start", ["Descargar y empezar", "Herunterladen und starten"]),
+    ("Skip", ["Omitir", "Überspringen"]),
+    ("Download complete!", ["¡Descarga completada!", "Download abgeschlossen!"]),
+    ("Download error:", ["Error de descarga:", "Downloadfehler:"]),
+    ("Downloaded model is damaged:", ["El modelo descargado está dañado:", "Das heruntergeladene Modell ist beschädigt:"]),
+    ("(custom)", ["(personalizado)", "(benutzerdefiniert)"]),
+    ("System", ["Sistema", "System"]),
+    ("Dark", ["Oscuro", "Dunkel"]),
+    ("Light", ["Claro", "Hell"]),
+    ("Times each model in the models folder on the same built-in clip, so results compare between machines.", ["Mide cada modelo de la carpeta de modelos con el mismo clip integrado, para comparar resultados entre equipos.", "Misst jedes Modell im Modellordner mit demselben eingebauten Clip, damit sich Ergebnisse zwischen Rechnern vergleichen lassen."]),
+    ("Running", ["Ejecutando", "Läuft:"]),
+    ("Output", ["Resultado", "Ausgabe"]),
+    ("(partial — cancelled)", ["(parcial — cancelado)", "(unvollständig — abgebrochen)"]),
+    ("Error:", ["Error:", "Fehler:"]),
+    ("Detected:", ["Detectado:", "Erkannt:"]),
+    ("Files added to the queue:", ["Archivos añadidos a la cola:", "Zur Warteschlange hinzugefügte Dateien:"]),
+    ("Batch stopped", ["Lote detenido", "Stapel angehalten"]),
+    ("Batch finished:", ["Lote terminado:", "Stapel fertig:"]),
+    ("done", ["hechos", "fertig"]),
+    ("failed", ["con error", "fehlgeschlagen"]),
+    ("listed in", ["listados en", "aufgeführt in"]),
+    ("couldn't save the failure list:", ["no se pudo guardar la lista de errores:", "Fehlerliste konnte nicht gespeichert werden:"]),
+    ("combined transcript saved to", ["transcripción combinada guardada en", "kombiniertes Transkript gespeichert unter"]),
+    ("couldn't save the combined transcript:", ["no se pudo guardar la transcripción combinada:", "kombiniertes Transkript konnte nicht gespeichert werden:"]),
+    ("source files deleted:", ["archivos de origen eliminados:", "gelöschte Quelldateien:"]),
+    ("the batch can't be resumed after a crash", ["el lote no se podrá reanudar tras un cierre inesperado", "der Stapel kann nach einem Absturz nicht fortgesetzt werden"]),
+    ("Select a model, then start the batch again", ["Selecciona un modelo y vuelve a iniciar el lote", "Wähle ein Modell und starte den Stapel erneut"]),
+    ("Files left to transcribe:", ["Archivos pendientes de transcribir:", "Noch zu transkribierende Dateien:"]),
+    ("Output folder:", ["Carpeta de salida:", "Ausgabeordner:"]),
+    ("Waiting files kept:", ["Archivos en espera conservados:", "Behaltene wartende Dateien:"]),
+    ("the queue lists the ones picked", ["la cola muestra los elegidos", "die Warteschlange zeigt die ausgewählten"]),
+];
@@ -0,0 +1,3206 @@
+//! Offline transcription with whisper.cpp, without any UI: audio decoding and resampling,
+//! model downloads, the transcription run itself, and transcript export formats.
+//! The desktop app and its command-line mode are built on top of this crate.
+
+pub mod align;
+pub mod normalize;
+
+use anyhow::{Context, Result, bail};
+use futures_util::StreamExt;
+use rubato::{Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, sync_channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSourceStream, ReadOnlySource};
+use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::{Hint, ProbeResult};
+use whisper_rs::{
+    DtwMode, DtwModelPreset, DtwParameters, FullParams, SamplingStrategy, SegmentCallbackData,
+    WhisperContext, WhisperContextParameters, WhisperError, WhisperSegment,
+};
+
+// Available Whisper models: name, file and a rough guide to what to expect from it
+pub const WHISPER_MODELS: &[(&str, &str, &str)] = &[
+    ("tiny", "ggml-tiny.bin", "fastest, lowest accuracy"),
+    ("base", "ggml-base.bin", "fast, fair accuracy"),
+    ("small", "ggml-small.bin", "balanced speed and accuracy"),
+    ("medium", "ggml-medium.bin", "slow, high accuracy"),
+    ("large", "ggml-large-v3-turbo.bin", "best accuracy, slow without a GPU"),
+];
+
+// Optional list in the models folder that replaces the built-in models
+pub const MODEL_MANIFEST_FILE: &str = "models.json";
+
+/// A model offered for download, from the built-in list or a models.json manifest
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub name: String,
+    pub filename: String,
+    // Empty downloads the file from the whisper.cpp repository on Hugging Face
+    #[serde(default)]
+    pub url: String,
+    // Rough guide to speed and accuracy, shown next to the name
+    #[serde(default)]
+    pub description: String,
+    // Checked once the download finishes, when given
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl ModelInfo {
+    pub fn download_url(&self) -> String {
+        if self.url.is_empty() {
+            get_model_url(&self.filename)
+        } else {
+            self.url.clone()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ModelManifest {
+    models: Vec<ModelInfo>,
+}
+
+/// The built-in model list, smallest first
+pub fn builtin_models() -> Vec<ModelInfo> {
+    WHISPER_MODELS
+        .iter()
+        .map(|(name, filename, description)| ModelInfo {
+            name: name.to_string(),
+            filename: filename.to_string(),
+            url: String::new(),
+            description: description.to_string(),
+            size: None,
+            sha256: None,
+        })
+        .collect()
+}
+
+/// Models offered for download, smallest first: the models.json manifest in the models folder
+/// if there is one, otherwise the built-in list. The message explains why a manifest was ignored.
+pub fn load_model_list(models_dir: &Path) -> (Vec<ModelInfo>, Option<String>) {
+    let path = models_dir.join(MODEL_MANIFEST_FILE);
+    if !path.is_file() {
+        return (builtin_models(), None);
+    }
+    match read_model_manifest(&path) {
+        Ok(models) => (models, None),
+        Err(e) => (builtin_models(), Some(format!("Ignoring {}: {:#}", path.display(), e))),
+    }
+}
+
+fn read_model_manifest(path: &Path) -> Result<Vec<ModelInfo>> {
+    let text = std::fs::read_to_string(path).context("Failed to read the file")?;
+    let manifest: ModelManifest = serde_json::from_str(&text).context("Invalid model list")?;
+    if manifest.models.is_empty() {
+        bail!("It lists no models");
+    }
+    for model in &manifest.models {
+        // Joined onto the models folder, so it mustn't point anywhere else
+        let plain = Path::new(&model.filename).file_name().and_then(|n| n.to_str()) == Some(model.filename.as_str());
+        if model.name.trim().is_empty() || !plain || !is_model_file(Path::new(&model.filename)) {
+            bail!("\"{}\" needs a name and a plain .bin file name", model.filename);
+        }
+    }
+    Ok(manifest.models)
+}
+
+pub fn get_model_url(filename: &str) -> String {
+    format!(
+        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/{}",
+        filename
+    )
+}
+
+// Supported audio formats
+pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "flac", "ogg", "m4a", "aac", "wma", "opus", "webm"];
+
+// whisper expects 16kHz mono input of at least one second
+pub const WHISPER_SAMPLE_RATE: usize = 16000;
+
+const MIN_AUDIO_SAMPLES: usize = WHISPER_SAMPLE_RATE * 11 / 10;
+
+// Auto-gain measures the level over half-second windows and lifts quiet ones towards the target
+const AUTO_GAIN_WINDOW: usize = WHISPER_SAMPLE_RATE / 2;
+const AUTO_GAIN_TARGET_DBFS: f32 = -20.0;
+const AUTO_GAIN_MAX_BOOST_DB: f32 = 24.0;
+// Windows below this are background noise or silence and are left alone
+const AUTO_GAIN_NOISE_FLOOR_DBFS: f32 = -55.0;
+
+// Pause before retrying a failed GPU context
+const GPU_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+// Formats written by "Export all..."
+pub const EXPORT_FORMATS: &[&str] = &["txt", "srt", "vtt", "json", "jsonl"];
+
+// Chapter titles are the chapter's opening words, cut off after this many
+const CHAPTER_TITLE_WORDS: usize = 8;
+
+// Formats that only make sense with segment timings
+pub const TIMED_FORMATS: &[&str] = &["srt", "vtt"];
+
+// Plain-text formats written with the chosen line endings; JSON keeps LF
+pub const TEXT_FORMATS: &[&str] = &["txt", "srt", "vtt"];
+
+// Warn about partial decodes once this share of packets couldn't be decoded
+const MAX_DROPPED_PACKET_PERCENT: usize = 1;
+
+// Default name for automatically saved output
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "{stem}.{ext}";
+
+// Phrases models tend to invent over silent endings, removed when they close a transcript
+pub const DEFAULT_TRAILING_PHRASES: &str = "Thanks for watching!
+Thank you for watching.
+Thank you so much for watching!
+Please like and subscribe.
+Subtitles by the Amara.org community";
+
+// A closing segment only counts as made up when whisper was at most this sure of it
+const TRAILING_PHRASE_MAX_CONFIDENCE: f32 = 0.75;
+
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+pub fn is_model_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("bin"))
+}
+
+/// Title/artist read from the audio file's metadata tags
+#[derive(Default)]
+pub struct AudioTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+}
+
+/// How fast a transcription ran relative to the audio length
+#[derive(Clone, Copy)]
+pub struct TranscribeStats {
+    pub audio_secs: f32,
+    pub elapsed_secs: f32,
+}
+
+impl TranscribeStats {
+    pub fn summary(&self) -> String {
+        format!(
+            "Transcribed {:.0}s of audio in {:.0}s ({:.1}x realtime)",
+            self.audio_secs,
+            self.elapsed_secs,
+            self.audio_secs / self.elapsed_secs.max(0.001)
+        )
+    }
+}
+
+/// A timed piece of the transcript. Timestamps are in centiseconds, as reported by whisper.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: i64,
+    pub end: i64,
+    pub text: String,
+    // Mean token probability (0-1); streamed segments report 1.0 until the run completes
+    pub confidence: f32,
+    // Token-level timings, only filled when DTW alignment is enabled
+    pub words: Vec<Word>,
+    // A tinydiarize model predicted a new speaker after this segment
+    pub speaker_turn: bool,
+    // Whisper's estimate (0-1) that the window held no speech; streamed segments report 0.0
+    #[serde(default)]
+    pub no_speech_prob: f32,
+}
+
+/// A single token with its DTW-aligned timing (centiseconds)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Word {
+    pub start: i64,
+    pub end: i64,
+    pub text: String,
+}
+
+pub enum DownloadMessage {
+    // Where the file is actually served from after following redirects
+    Source { host: String, url: String },
+    Progress { downloaded: u64, total: u64 },
+    Verifying,
+    Done(PathBuf),
+    Error(String),
+    Invalid(String),
+}
+
+/// Timing for one model on the benchmark clip; `output` holds the error when the run failed
+pub struct BenchmarkResult {
+    pub model: String,
+    pub elapsed_secs: f32,
+    pub output: Result<String, String>,
+}
+
+pub enum BenchmarkMessage {
+    // Name of the model now being timed
+    Running(String),
+    Result(BenchmarkResult),
+    Done,
+}
+
+/// Whisper parameters chosen in the UI, snapshotted for each transcription run
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscribeOptions {
+    // None lets whisper auto-detect the spoken language
+    pub language: Option<String>,
+    pub use_gpu: bool,
+    // None uses whisper's default thread count
+    pub n_threads: Option<i32>,
+    // Text that primes the decoder with vocabulary/style
+    pub initial_prompt: String,
+    // None keeps whisper's default text context size
+    pub max_text_ctx: Option<i32>,
+    pub single_segment: bool,
+    // whisper.cpp defaults to decoding each window without the previous text
+    pub condition_on_previous: bool,
+    // Greedy sampling candidates; 1 is fastest
+    pub best_of: i32,
+    pub temperature: f32,
+    // Added to the temperature each time a decode fails the quality checks
+    pub temperature_inc: f32,
+    // DTW alignment for precise token timestamps (needs a standard model)
+    pub dtw: bool,
+    // Mark speaker turns (needs a tinydiarize "tdrz" model)
+    pub tinydiarize: bool,
+    // Text only: skip timestamp tokens, which is faster but rules out subtitle export
+    pub no_timestamps: bool,
+    // Keep special tokens such as [_BEG_] and <|endoftext|> in the segment text, for debugging
+    pub show_special_tokens: bool,
+    // Skip transcription when the overall level is below this (dBFS); None disables the check
+    pub silence_threshold_db: Option<f32>,
+    // Lift quiet passages towards the level of loud ones: 0 is off, 1 evens them out fully
+    pub auto_gain: f32,
+    // Fixed filter run over the audio before transcribing, for hum, phone lines or hiss
+    pub audio_filter: AudioFilter,
+    // Silence put before the audio so a clip's opening word isn't cut off; 0 adds none
+    pub lead_in_ms: u32,
+    // Only transcribe the start of the audio; used by the model benchmark
+    #[serde(skip)]
+    pub duration_ms: Option<i32>,
+    // Only transcribe this range (seconds), picked on the waveform
+    #[serde(skip)]
+    pub selection: Option<(f32, f32)>,
+    // Ranges (seconds on the source file) taken out before transcribing, such as ad breaks
+    #[serde(skip)]
+    pub cuts: Vec<(f32, f32)>,
+    // Give times on the source file instead of on the audio left after the cuts
+    pub cut_times_original: bool,
+    // Anti-aliasing used when converting input to 16kHz
+    pub resampler: ResamplerOptions,
+    // Read audio files through a memory map instead of buffered reads
+    pub mmap_audio: bool,
+    // Let whisper print progress to stderr; only the CLI turns this on
+    #[serde(skip)]
+    pub print_progress: bool,
+    // Run the audio again on CPU and flag segments where the GPU output differs
+    pub verify_on_cpu: bool,
+    // Give up on the GPU and run on CPU when its context takes longer than this to load; 0 waits forever
+    pub gpu_timeout_secs: u32,
+}
+
+/// Window functions offered for resampling, mirroring rubato's
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ResampleWindow {
+    Blackman,
+    Blackman2,
+    BlackmanHarris,
+    BlackmanHarris2,
+    Hann,
+    Hann2,
+}
+
+impl ResampleWindow {
+    pub const ALL: [ResampleWindow; 6] = [
+        ResampleWindow::Blackman,
+        ResampleWindow::Blackman2,
+        ResampleWindow::BlackmanHarris,
+        ResampleWindow::BlackmanHarris2,
+        ResampleWindow::Hann,
+        ResampleWindow::Hann2,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ResampleWindow::Blackman => "Blackman",
+            ResampleWindow::Blackman2 => "Blackman²",
+            ResampleWindow::BlackmanHarris => "Blackman-Harris",
+            ResampleWindow::BlackmanHarris2 => "Blackman-Harris²",
+            ResampleWindow::Hann => "Hann",
+            ResampleWindow::Hann2 => "Hann²",
+        }
+    }
+
+    pub fn to_rubato(self) -> WindowFunction {
+        match self {
+            ResampleWindow::Blackman => WindowFunction::Blackman,
+            ResampleWindow::Blackman2 => WindowFunction::Blackman2,
+            ResampleWindow::BlackmanHarris => WindowFunction::BlackmanHarris,
+            ResampleWindow::BlackmanHarris2 => WindowFunction::BlackmanHarris2,
+            ResampleWindow::Hann => WindowFunction::Hann,
+            ResampleWindow::Hann2 => WindowFunction::Hann2,
+        }
+    }
+}
+
+/// Filter presets for common interference, run on the 16 kHz mono audio before whisper sees it
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AudioFilter {
+    None,
+    // Notches on mains hum and its first harmonics, plus a high-pass under them
+    Hum,
+    // Only the 300-3400 Hz band a phone line carries
+    Telephone,
+    // Gentle low-pass over the range where hiss sits but speech has little energy
+    Hiss,
+}
+
+impl AudioFilter {
+    pub const ALL: [AudioFilter; 4] = [
+        AudioFilter::None,
+        AudioFilter::Hum,
+        AudioFilter::Telephone,
+        AudioFilter::Hiss,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AudioFilter::None => "None",
+            AudioFilter::Hum => "Remove 60Hz hum",
+            AudioFilter::Telephone => "Telephone band-pass for call recordings",
+            AudioFilter::Hiss => "Reduce hiss",
+        }
+    }
+
+    fn stages(self) -> Vec<Biquad> {
+        match self {
+            AudioFilter::None => Vec::new(),
+            AudioFilter::Hum => vec![
+                Biquad::high_pass(40.0, 0.707),
+                Biquad::notch(60.0, 10.0),
+                Biquad::notch(120.0, 10.0),
+                Biquad::notch(180.0, 10.0),
+            ],
+            AudioFilter::Telephone => vec![
+                Biquad::high_pass(300.0, 0.707),
+                Biquad::high_pass(300.0, 0.707),
+                Biquad::low_pass(3400.0, 0.707),
+                Biquad::low_pass(3400.0, 0.707),
+            ],
+            AudioFilter::Hiss => vec![Biquad::low_pass(5500.0, 0.707)],
+        }
+    }
+}
+
+/// One second-order IIR section (RBJ audio EQ cookbook), run in direct form I
+struct Biquad {
+    b: [f32; 3],
+    a: [f32; 2],
+    x: [f32; 2],
+    y: [f32; 2],
+}
+
+impl Biquad {
+    /// Coefficients from the cookbook's b0..b2, a0..a2, normalized by a0
+    fn new(b: [f32; 3], a: [f32; 3]) -> Self {
+        Biquad {
+            b: [b[0] / a[0], b[1] / a[0], b[2] / a[0]],
+            a: [a[1] / a[0], a[2] / a[0]],
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    /// cos(w0) and alpha for a corner or centre frequency at the whisper sample rate
+    fn params(freq: f32, q: f32) -> (f32, f32) {
+        let w0 = 2.0 * std::f32::consts::PI * freq / WHISPER_SAMPLE_RATE as f32;
+        (w0.cos(), w0.sin() / (2.0 * q))
+    }
+
+    fn notch(freq: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::params(freq, q);
+        Self::new([1.0, -2.0 * cos, 1.0], [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    fn high_pass(freq: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::params(freq, q);
+        let b = (1.0 + cos) / 2.0;
+        Self::new([b, -(1.0 + cos), b], [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    fn low_pass(freq: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::params(freq, q);
+        let b = (1.0 - cos) / 2.0;
+        Self::new([b, 1.0 - cos, b], [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.b[0] * input + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [input, self.x[0]];
+        self.y = [output, self.y[0]];
+        output
+    }
+}
+
+/// Run a filter preset over 16 kHz mono samples in place
+pub fn apply_audio_filter(samples: &mut [f32], filter: AudioFilter) {
+    let mut stages = filter.stages();
+    if stages.is_empty() {
+        return;
+    }
+    for sample in samples.iter_mut() {
+        *sample = stages.iter_mut().fold(*sample, |s, stage| stage.process(s));
+    }
+}
+
+/// Line endings for saved text transcripts
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LineEnding {
+    // CRLF on Windows, LF elsewhere
+    Native,
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub const ALL: [LineEnding; 3] = [LineEnding::Native, LineEnding::Lf, LineEnding::CrLf];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LineEnding::Native => "System default",
+            LineEnding::Lf => "LF (Unix)",
+            LineEnding::CrLf => "CRLF (Windows)",
+        }
+    }
+
+    /// Rewrite LF-terminated text with these line endings
+    pub fn apply(self, text: &str) -> String {
+        let crlf = match self {
+            LineEnding::Native => cfg!(windows),
+            LineEnding::Lf => false,
+            LineEnding::CrLf => true,
+        };
+        if crlf {
+            text.replace("\r\n", "\n").replace('\n', "\r\n")
+        } else {
+            text.replace("\r\n", "\n")
+        }
+    }
+}
+
+/// Unit of the timestamps in JSON and JSON Lines exports
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TimeUnit {
+    Seconds,
+    Milliseconds,
+    // whisper's own resolution
+    Centiseconds,
+}
+
+impl TimeUnit {
+    pub const ALL: [TimeUnit; 3] = [TimeUnit::Seconds, TimeUnit::Milliseconds, TimeUnit::Centiseconds];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeUnit::Seconds => "Seconds (decimal)",
+            TimeUnit::Milliseconds => "Milliseconds",
+            TimeUnit::Centiseconds => "Centiseconds",
+        }
+    }
+
+    /// Name written into JSON exports and accepted by --time-unit
+    pub fn name(self) -> &'static str {
+        match self {
+            TimeUnit::Seconds => "seconds",
+            TimeUnit::Milliseconds => "milliseconds",
+            TimeUnit::Centiseconds => "centiseconds",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "seconds" | "s" => Some(TimeUnit::Seconds),
+            "milliseconds" | "ms" => Some(TimeUnit::Milliseconds),
+            "centiseconds" | "cs" => Some(TimeUnit::Centiseconds),
+            _ => None,
+        }
+    }
+
+    /// A whisper timestamp (centiseconds) as a JSON number in this unit
+    fn json(self, cs: i64) -> serde_json::Value {
+        match self {
+            TimeUnit::Seconds => centiseconds_to_secs(cs).into(),
+            TimeUnit::Milliseconds => (cs * 10).into(),
+            TimeUnit::Centiseconds => cs.into(),
+        }
+    }
+}
+
+/// Sinc resampler tuning for careful archival work
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResamplerOptions {
+    pub window: ResampleWindow,
+    // Cutoff relative to the Nyquist frequency of the lower rate
+    pub f_cutoff: f32,
+    pub oversampling_factor: usize,
+}
+
+impl Default for ResamplerOptions {
+    fn default() -> Self {
+        Self {
+            window: ResampleWindow::BlackmanHarris2,
+            f_cutoff: 0.95,
+            oversampling_factor: 256,
+        }
+    }
+}
+
+impl Default for TranscribeOptions {
+    fn default() -> Self {
+        Self {
+            language: None,
+            use_gpu: true,
+            n_threads: None,
+            initial_prompt: String::new(),
+            max_text_ctx: None,
+            single_segment: false,
+            condition_on_previous: false,
+            best_of: 1,
+            temperature: 0.0,
+            temperature_inc: 0.2,
+            dtw: false,
+            tinydiarize: false,
+            no_timestamps: false,
+            show_special_tokens: false,
+            silence_threshold_db: Some(-60.0),
+            auto_gain: 0.0,
+            audio_filter: AudioFilter::None,
+            lead_in_ms: 0,
+            duration_ms: None,
+            selection: None,
+            cuts: Vec::new(),
+            cut_times_original: false,
+            resampler: ResamplerOptions::default(),
+            mmap_audio: false,
+            print_progress: false,
+            verify_on_cpu: false,
+            gpu_timeout_secs: 60,
+        }
+    }
+}
+
+/// Settings applied to the HTTP client used for model downloads
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    // Empty uses the HTTP_PROXY/HTTPS_PROXY environment variables, if set
+    pub proxy_url: String,
+    pub proxy_username: String,
+    // Not persisted to disk
+    #[serde(skip)]
+    pub proxy_password: String,
+    // Extra CA certificates (PEM or DER) to trust, for networks that inspect TLS traffic
+    pub ca_cert_path: Option<PathBuf>,
+}
+
+/// A file's stem as text, or `fallback` when it has none
+pub fn file_stem_or(path: &Path, fallback: &str) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// whisper's short code (e.g. "en") for a language name such as "English"; codes map to themselves
+pub fn language_code(name: &str) -> Option<String> {
+    whisper_rs::get_lang_id(&name.to_lowercase())
+        .and_then(whisper_rs::get_lang_str)
+        .map(str::to_string)
+}
+
+/// Display name for a whisper language code, e.g. "Spanish" for "es"
+pub fn language_name(code: &str) -> Option<String> {
+    whisper_rs::get_lang_id(code)
+        .and_then(whisper_rs::get_lang_str_full)
+        .map(capitalize)
+}
+
+/// Every language whisper supports as (display name, code), sorted by name
+pub fn spoken_languages() -> Vec<(String, &'static str)> {
+    let mut languages: Vec<(String, &'static str)> = (0..=whisper_rs::get_lang_max_id())
+        .filter_map(|id| {
+            let code = whisper_rs::get_lang_str(id)?;
+            let name = whisper_rs::get_lang_str_full(id)?;
+            Some((capitalize(name), code))
+        })
+        .collect();
+    languages.sort();
+    languages
+}
+
+/// Expand an output name template such as `{stem}_{model}_{lang}.{ext}` into a legal file name.
+/// The extension is appended when the template doesn't place `{ext}` itself.
+pub fn expand_output_template(template: &str, stem: &str, model: &str, lang: &str, ext: &str) -> Result<String> {
+    let mut name = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        name.push_str(&rest[..open]);
+        let close = rest[open..]
+            .find('}')
+            .with_context(|| format!("Unclosed '{{' in name template \"{}\"", template))?;
+        let token = &rest[open + 1..open + close];
+        name.push_str(match token {
+            "stem" => stem,
+            "model" => model,
+            "lang" => lang,
+            "ext" => ext,
+            _ => bail!("Unknown name template token {{{}}}; use {{stem}}, {{model}}, {{lang}} or {{ext}}", token),
+        });
+        rest = &rest[open + close + 1..];
+    }
+    name.push_str(rest);
+    if !template.contains("{ext}") {
+        name = format!("{}.{}", name, ext);
+    }
+
+    let name = sanitize_file_name(&name);
+    if name.is_empty() || name.starts_with('.') {
+        bail!("Name template \"{}\" doesn't produce a usable file name", template);
+    }
+    Ok(name)
+}
+
+/// Where a transcription run reads its audio from
+#[derive(Clone)]
+pub enum AudioInput {
+    File(PathBuf),
+    // Several files decoded in order and joined into one continuous stream
+    Joined(Vec<PathBuf>),
+    Stdin,
+    // Audio the UI already decoded for the same file and settings
+    Decoded(Arc<DecodedAudio>),
+}
+
+/// GPU backend compiled into this build, if any
+pub const GPU_BACKEND: Option<&str> = if cfg!(feature = "cuda") {
+    Some("CUDA")
+} else if cfg!(feature = "vulkan") {
+    Some("Vulkan")
+} else {
+    None
+};
+
+/// Check whether the compiled-in GPU backend looks usable
+pub fn check_gpu_available() -> bool {
+    match GPU_BACKEND {
+        // The CUDA toolkit installer sets CUDA_PATH; a missing runtime still shows up at model load
+        Some("CUDA") => std::env::var("CUDA_PATH").is_ok(),
+        // The Vulkan loader is part of the graphics driver, so ask ggml for a device
+        Some(_) => gpu_device_name().is_some(),
+        None => false,
+    }
+}
+
+pub enum TranscribeMessage {
+    Status(String),
+    // Whether the GPU is in use, plus the backend/device when known
+    GpuStatus(bool, Option<String>),
+    DetectedLanguage(String),
+    Warning(String),
+    // A finished segment, streamed while whisper is still running
+    Segment(Segment),
+    // Percent of the audio whisper has processed
+    Progress(i32),
+    ClearSegments,
+    Disagreements(Vec<Disagreement>),
+    Done(Vec<Segment>, TranscribeStats),
+    Cancelled,
+    Error(String),
+}
+
+/// The result of [`transcribe`]
+pub struct Transcript {
+    pub segments: Vec<Segment>,
+    // Language whisper detected, when it was asked to auto-detect
+    pub language: Option<String>,
+    // Non-fatal problems, such as options the model doesn't support
+    pub warnings: Vec<String>,
+    pub stats: TranscribeStats,
+}
+
+/// Part of a recording between two pauses, titled with its opening words
+pub struct Chapter {
+    // Centiseconds, like segment times
+    pub start: i64,
+    pub end: i64,
+    pub title: String,
+}
+
+/// Progress reported to the callback of [`transcribe_with`]
+pub enum TranscribeEvent {
+    // Stage of the run, e.g. "Loading audio..."
+    Status(String),
+    // Percent of the audio whisper has processed, 0-100
+    Progress(i32),
+    // A finished segment, streamed while whisper is still running (not with DTW alignment)
+    Segment(Segment),
+    // The GPU run failed and restarts on CPU; segments streamed so far are superseded
+    Restarted,
+    Warning(String),
+    Done(TranscribeStats),
+}
+
+/// Transcribe an audio file with a whisper model, blocking until it finishes
+pub fn transcribe(model: &Path, audio: &Path, options: &TranscribeOptions) -> Result<Transcript> {
+    transcribe_with(model, audio, options, |_| {})
+}
+
+/// Like [`transcribe`], calling `on_event` from this thread as the run progresses
+pub fn transcribe_with(
+    model: &Path,
+    audio: &Path,
+    options: &TranscribeOptions,
+    mut on_event: impl FnMut(TranscribeEvent),
+) -> Result<Transcript> {
+    let (tx, rx) = channel();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let model = model.to_path_buf();
+    let audio = AudioInput::File(audio.to_path_buf());
+    let options = options.clone();
+    thread::spawn(move || run_transcription(model, audio, options, cancel, tx));
+
+    let mut language = None;
+    let mut warnings = Vec::new();
+    for msg in rx {
+        match msg {
+            TranscribeMessage::Status(s) => on_event(TranscribeEvent::Status(s)),
+            TranscribeMessage::Progress(p) => on_event(TranscribeEvent::Progress(p)),
+            TranscribeMessage::Segment(segment) => on_event(TranscribeEvent::Segment(segment)),
+            TranscribeMessage::ClearSegments => on_event(TranscribeEvent::Restarted),
+            TranscribeMessage::DetectedLanguage(name) => language = Some(name),
+            TranscribeMessage::Warning(w) => {
+                on_event(TranscribeEvent::Warning(w.clone()));
+                warnings.push(w);
+            }
+            TranscribeMessage::Done(segments, stats) => {
+                on_event(TranscribeEvent::Done(stats));
+                return Ok(Transcript {
+                    segments,
+                    language,
+                    warnings,
+                    stats,
+                });
+            }
+            TranscribeMessage::Error(e) => bail!(e),
+            TranscribeMessage::Cancelled => bail!("Transcription cancelled"),
+            TranscribeMessage::GpuStatus(..) | TranscribeMessage::Disagreements(_) => {}
+        }
+    }
+    bail!("Transcription ended without a result")
+}
+
+pub fn run_transcription(
+    model_path: PathBuf,
+    audio: AudioInput,
+    options: TranscribeOptions,
+    cancel: Arc<AtomicBool>,
+    tx: Sender<TranscribeMessage>,
+) {
+    let mut options = options;
+    if options.tinydiarize && !is_tinydiarize_model(&model_path) {
+        tx.send(TranscribeMessage::Warning(
+            "Speaker turns need a tinydiarize (tdrz) model".to_string(),
+        ))
+        .ok();
+        options.tinydiarize = false;
+    }
+
+    let result = (|| -> Result<(Vec<Segment>, TranscribeStats)> {
+        tx.send(TranscribeMessage::Status("Loading model...".to_string()))
+            .ok();
+
+        let dtw = if options.dtw {
+            let preset = dtw_preset_for_model(&model_path);
+            if preset.is_none() {
+                tx.send(TranscribeMessage::Warning(
+                    "Word alignment (DTW) isn't available for this model".to_string(),
+                ))
+                .ok();
+            }
+            preset
+        } else {
+            None
+        };
+
+        // Try GPU first (unless disabled), fallback to CPU if it fails
+        let gpu_ctx = if options.use_gpu {
+            let timeout = (options.gpu_timeout_secs > 0)
+                .then(|| Duration::from_secs(options.gpu_timeout_secs.into()));
+            let mut attempt = load_gpu_context(&model_path, dtw.clone(), timeout);
+            if matches!(attempt, Some(Err(_))) {
+                // Driver hiccups sometimes fail the first attempt; give it one more go
+                thread::sleep(GPU_RETRY_DELAY);
+                attempt = load_gpu_context(&model_path, dtw.clone(), timeout);
+                if matches!(attempt, Some(Ok(_))) {
+                    tx.send(TranscribeMessage::Warning(
+                        "GPU initialization failed once, succeeded on retry".to_string(),
+                    ))
+                    .ok();
+                }
+            }
+            match attempt {
+                Some(Ok(c)) => Some(c),
+                Some(Err(e)) => {
+                    tx.send(TranscribeMessage::Warning(format!(
+                        "GPU unavailable ({}), running on CPU",
+                        e
+                    )))
+                    .ok();
+                    None
+                }
+                None => {
+                    tx.send(TranscribeMessage::Warning(format!(
+                        "GPU initialization didn't finish within {} s, running on CPU",
+                        options.gpu_timeout_secs
+                    )))
+                    .ok();
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let (mut ctx, mut using_gpu) = match gpu_ctx {
+            Some(c) => (c, true),
+            None => {
+                let c = match load_whisper_context(&model_path, false, dtw.clone()) {
+                    Ok(c) => c,
+                    // whisper.cpp's own error doesn't say why; the header often does
+                    Err(e) => match model_format_problem(&model_path) {
+                        Some(problem) => bail!("Failed to load Whisper model: {}", problem),
+                        None => return Err(e.context("Failed to load Whisper model")),
+                    },
+                };
+                (c, false)
+            }
+        };
+
+        let device = if using_gpu { gpu_device_name() } else { None };
+        tx.send(TranscribeMessage::GpuStatus(using_gpu, device)).ok();
+        if let Some(warning) = model_name_mismatch(&model_path, &ctx) {
+            tx.send(TranscribeMessage::Warning(warning)).ok();
+        }
+
+        tx.send(TranscribeMessage::Status("Loading audio...".to_string()))
+            .ok();
+
+        let decoded = match audio {
+            AudioInput::File(ref path) => {
+                load_audio_to_mono_16khz(path, &options.resampler, options.mmap_audio)?
+            }
+            AudioInput::Joined(ref paths) => {
+                let mut joined = DecodedAudio {
+                    samples: Vec::new(),
+                    packets: 0,
+                    dropped_packets: 0,
+                    linear_resampled: false,
+                    probed_without_hint: false,
+                };
+                for path in paths {
+                    let part = load_audio_to_mono_16khz(path, &options.resampler, options.mmap_audio)
+                        .with_context(|| format!("Failed to load {}", path.display()))?;
+                    joined.samples.extend_from_slice(&part.samples);
+                    joined.packets += part.packets;
+                    joined.dropped_packets += part.dropped_packets;
+                    joined.linear_resampled |= part.linear_resampled;
+                    joined.probed_without_hint |= part.probed_without_hint;
+                }
+                joined
+            }
+            AudioInput::Stdin => load_stdin_to_mono_16khz(&options.resampler)?,
+            AudioInput::Decoded(ref audio) => DecodedAudio {
+                samples: audio.samples.clone(),
+                packets: audio.packets,
+                dropped_packets: audio.dropped_packets,
+                linear_resampled: audio.linear_resampled,
+                probed_without_hint: audio.probed_without_hint,
+            },
+        };
+        if decoded.probed_without_hint {
+            tx.send(TranscribeMessage::Warning(
+                "The file extension didn't match the audio; its format was recognized from the content".to_string(),
+            ))
+            .ok();
+        }
+        if decoded.is_partial() {
+            tx.send(TranscribeMessage::Warning(format!(
+                "Decoding was partial: skipped {} of {} damaged packets, so the transcript may have gaps",
+                decoded.dropped_packets, decoded.packets
+            )))
+            .ok();
+        }
+        if decoded.linear_resampled {
+            tx.send(TranscribeMessage::Warning(
+                "The audio's sample rate needed a simpler resampler, which may cost some accuracy".to_string(),
+            ))
+            .ok();
+        }
+        let mut audio_data = decoded.samples;
+
+        // The selection was picked on the source, so it moves onto what's left after the cuts
+        if !options.cuts.is_empty() {
+            audio_data = remove_cuts(&audio_data, &options.cuts);
+            let cut = |secs: f32| cut_timestamp((secs * 100.0) as i64, &options.cuts) as f32 / 100.0;
+            options.selection = options.selection.map(|(start, end)| (cut(start), cut(end)));
+        }
+
+        // Transcribe just the selected range, shifting timestamps back onto the full file
+        if let Some((start, end)) = options.selection {
+            let from = ((start * WHISPER_SAMPLE_RATE as f32) as usize).min(audio_data.len());
+            let to = ((end * WHISPER_SAMPLE_RATE as f32) as usize).clamp(from, audio_data.len());
+            audio_data = audio_data[from..to].to_vec();
+        }
+
+        if cancel.load(Ordering::Relaxed) {
+            bail!("Cancelled");
+        }
+
+        // An empty selection or a range that was cut out entirely ends up here too
+        if audio_data.is_empty() {
+            bail!("Audio file contains no samples");
+        }
+
+        if let Some(threshold) = options.silence_threshold_db {
+            let level = rms_dbfs(&audio_data);
+            if level < threshold {
+                bail!(
+                    "Audio appears to be silent ({:.0} dBFS, below the {:.0} dBFS threshold)",
+                    level,
+                    threshold
+                );
+            }
+        }
+        apply_audio_filter(&mut audio_data, options.audio_filter);
+        apply_auto_gain(&mut audio_data, options.auto_gain);
+
+        // whisper rejects or hallucinates on inputs under a second, so pad short clips with silence
+        let audio_len_cs = (audio_data.len() * 100 / WHISPER_SAMPLE_RATE) as i64;
+        let audio_secs = audio_data.len() as f32 / WHISPER_SAMPLE_RATE as f32;
+        // Optional silence in front gives short clips a lead-in before their first word
+        let lead_in_cs = options.lead_in_ms as i64 / 10;
+        if lead_in_cs > 0 {
+            let mut padded = vec![0.0; lead_in_cs as usize * WHISPER_SAMPLE_RATE / 100];
+            padded.extend_from_slice(&audio_data);
+            audio_data = padded;
+        }
+        if audio_data.len() < MIN_AUDIO_SAMPLES {
+            audio_data.resize(MIN_AUDIO_SAMPLES, 0.0);
+        }
+
+        tx.send(TranscribeMessage::Status("Transcribing...".to_string()))
+            .ok();
+
+        // Stream segments to the UI as they finish; DTW timings only exist once the run completes
+        let stream_segments = dtw.is_none();
+
+        let started = Instant::now();
+        let mut state = match ctx.create_state() {
+            Ok(state) => state,
+            Err(e) if using_gpu => {
+                // The GPU buffers for a state are allocated here, so a busy card can fail after the model loaded
+                tx.send(TranscribeMessage::Warning(format!(
+                    "Couldn't allocate GPU memory for this run ({}), running on CPU",
+                    e
+                )))
+                .ok();
+                tx.send(TranscribeMessage::GpuStatus(false, None)).ok();
+                using_gpu = false;
+                ctx = load_whisper_context(&model_path, false, dtw.clone())
+                    .context("Failed to load Whisper model")?;
+                ctx.create_state().context("Failed to create state")?
+            }
+            Err(e) => return Err(e).context("Failed to create state"),
+        };
+        match state.full(build_full_params(&options, &cancel, tx.clone(), stream_segments), &audio_data) {
+            Ok(()) => {}
+            Err(_) if cancel.load(Ordering::Relaxed) => bail!("Cancelled"),
+            Err(e) => {
+                // Large models on long audio can exhaust VRAM mid-run; CPU is slower but works
+                let Some(warning) = gpu_failure_warning(&e).filter(|_| using_gpu) else {
+                    return Err(e).context("Failed to transcribe audio");
+                };
+                tx.send(TranscribeMessage::Warning(warning.to_string())).ok();
+                tx.send(TranscribeMessage::GpuStatus(false, None)).ok();
+                tx.send(TranscribeMessage::ClearSegments).ok();
+
+                let cpu_ctx = load_whisper_context(&model_path, false, dtw.clone())
+                    .context("Failed to load Whisper model")?;
+                state = cpu_ctx.create_state().context("Failed to create state")?;
+                state
+                    .full(build_full_params(&options, &cancel, tx.clone(), stream_segments), &audio_data)
+                    .context("Failed to transcribe audio")?;
+            }
+        }
+
+        if options.language.is_none() {
+            let lang_id = state.full_lang_id_from_state();
+            if let Some(name) = whisper_rs::get_lang_str_full(lang_id) {
+                tx.send(TranscribeMessage::DetectedLanguage(capitalize(name)))
+                    .ok();
+            }
+        }
+
+        let num_segments = state.full_n_segments();
+        let mut segments = Vec::new();
+
+        for i in 0..num_segments {
+            if let Some(segment) = state.get_segment(i) {
+                if let Ok(text) = segment.to_str_lossy() {
+                    // Anything in the padding on either side is made up
+                    if segment.start_timestamp() >= audio_len_cs + lead_in_cs
+                        || (lead_in_cs > 0 && segment.end_timestamp() <= lead_in_cs)
+                    {
+                        continue;
+                    }
+                    let words = if dtw.is_some() {
+                        collect_words(&segment, ctx.token_eot())
+                            .into_iter()
+                            .map(|w| Word {
+                                start: shift_timestamp(w.start, &options),
+                                end: shift_timestamp(w.end, &options),
+                                text: w.text,
+                            })
+                            .collect()
+                    } else {
+                        Vec::new()
+                    };
+                    segments.push(Segment {
+                        start: shift_timestamp(segment.start_timestamp(), &options),
+                        end: shift_timestamp(
+                            segment.end_timestamp().min(audio_len_cs + lead_in_cs),
+                            &options,
+                        ),
+                        text: text.into_owned(),
+                        confidence: segment_confidence(&segment, ctx.token_eot()),
+                        words,
+                        speaker_turn: options.tinydiarize && segment.next_segment_speaker_turn(),
+                        no_speech_prob: segment.no_speech_probability(),
+                    });
+                }
+            }
+        }
+
+        let stats = TranscribeStats {
+            audio_secs,
+            elapsed_secs: started.elapsed().as_secs_f32(),
+        };
+        Ok((segments, stats))
+    })();
+
+    match result {
+        Ok((segments, stats)) => {
+            // Silence or noise whisper found nothing in; say so rather than finish with a blank transcript
+            if segments.iter().all(|s| s.text.trim().is_empty()) {
+                tx.send(TranscribeMessage::Warning("No speech detected in the audio".to_string()))
+                    .ok();
+            }
+            tx.send(TranscribeMessage::Done(segments, stats)).ok();
+        }
+        Err(_) if cancel.load(Ordering::Relaxed) => {
+            tx.send(TranscribeMessage::Cancelled).ok();
+        }
+        Err(e) => {
+            tx.send(TranscribeMessage::Error(e.to_string())).ok();
+        }
+    }
+}
+
+/// Describe the first GPU ggml registered, as "<backend>: <device>"
+fn gpu_device_name() -> Option<String> {
+    use whisper_rs::whisper_rs_sys as sys;
+    use std::ffi::CStr;
+
+    // SAFETY: ggml builds its device registry once, on first use,
+    // and the returned strings live as long as the devices themselves.
+    unsafe {
+        for i in 0..sys::ggml_backend_dev_count() {
+            let dev = sys::ggml_backend_dev_get(i);
+            if dev.is_null()
+                || sys::ggml_backend_dev_type(dev)
+                    != sys::ggml_backend_dev_type_GGML_BACKEND_DEVICE_TYPE_GPU
+            {
+                continue;
+            }
+            let name = CStr::from_ptr(sys::ggml_backend_dev_name(dev)).to_string_lossy();
+            let description =
+                CStr::from_ptr(sys::ggml_backend_dev_description(dev)).to_string_lossy();
+            // Device names are the backend plus an index, e.g. "CUDA0"
+            let backend = name.trim_end_matches(|c: char| c.is_ascii_digit());
+            return Some(format!("{}: {}", backend, description.trim()));
+        }
+    }
+    None
+}
+
+/// Load a GPU context on a thread of its own, so a driver that hangs can't stall the run.
+/// None when it didn't finish within `timeout`; that thread is then left behind, and a context
+/// it loads after all is dropped.
+fn load_gpu_context(
+    model_path: &Path,
+    dtw: Option<DtwModelPreset>,
+    timeout: Option<Duration>,
+) -> Option<Result<WhisperContext>> {
+    let Some(timeout) = timeout else {
+        return Some(load_whisper_context(model_path, true, dtw));
+    };
+    let (tx, rx) = channel();
+    let model_path = model_path.to_path_buf();
+    thread::spawn(move || {
+        tx.send(load_whisper_context(&model_path, true, dtw)).ok();
+    });
+    rx.recv_timeout(timeout).ok()
+}
+
+fn load_whisper_context(
+    model_path: &Path,
+    use_gpu: bool,
+    dtw: Option<DtwModelPreset>,
+) -> Result<WhisperContext> {
+    let mut ctx_params = WhisperContextParameters::default();
+    ctx_params.use_gpu(use_gpu);
+    if let Some(model_preset) = dtw {
+        ctx_params.dtw_parameters(DtwParameters {
+            mode: DtwMode::ModelPreset { model_preset },
+            ..Default::default()
+        });
+    }
+    // whisper.cpp opens the file with a narrow path, which on Windows goes through the ANSI
+    // code page and fails for many non-ASCII paths (e.g. a user folder named "José"), and a
+    // path that isn't UTF-8 can't be passed at all. Those are read through a memory map instead.
+    match model_path.to_str().filter(|p| p.is_ascii()) {
+        Some(path) => Ok(WhisperContext::new_with_params(path, ctx_params)?),
+        None => {
+            let file = File::open(model_path)
+                .with_context(|| format!("Failed to open model file {}", model_path.display()))?;
+            // SAFETY: the map is read-only and only read while whisper copies the weights out
+            let map = unsafe { memmap2::Mmap::map(&file) }.context("Failed to memory-map model file")?;
+            Ok(WhisperContext::new_from_buffer_with_params(&map, ctx_params)?)
+        }
+    }
+}
+
+/// Whether a model understands languages other than English, read from its header.
+/// English-only (".en") models have a smaller vocabulary; None when the file can't be read.
+pub fn model_is_multilingual(path: &Path) -> Option<bool> {
+    // Magic, then n_vocab as the first hyperparameter
+    let mut header = [0u8; 8];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+    if header[..4] != GGML_MAGIC {
+        return None;
+    }
+    let n_vocab = i32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    Some(n_vocab >= MULTILINGUAL_VOCAB)
+}
+
+// Vocabulary size of the multilingual models; English-only ones have 51864
+const MULTILINGUAL_VOCAB: i32 = 51865;
+
+// Versions of the whisper bindings this build links, read from Cargo.lock by build.rs
+pub const WHISPER_RS_VERSION: &str = env!("WHISPER_RS_VERSION");
+pub const WHISPER_RS_SYS_VERSION: &str = env!("WHISPER_RS_SYS_VERSION");
+
+// Newest ggml weight type this build's whisper.cpp loads (Q6_K)
+const MAX_KNOWN_FTYPE: i32 = 14;
+
+// whisper.cpp folds the quantization version into ftype as version * 1000 + type
+const QUANTIZATION_VERSION_FACTOR: i32 = 1000;
+
+/// Backend and CPU features whisper.cpp was built with, e.g. "AVX = 1 | AVX2 = 1 | ..."
+pub fn whisper_system_info() -> String {
+    whisper_rs::print_system_info().trim().to_string()
+}
+
+/// Why a model file can't be loaded by this build, judged from its header: a GGUF file, or
+/// a ggml file using a weight type or mel layout newer than the bundled whisper.cpp knows.
+/// None when the header looks loadable or the file can't be read.
+pub fn model_format_problem(path: &Path) -> Option<String> {
+    // Magic, then 11 hyperparameters: vocab, 4 audio, 4 text, n_mels and ftype
+    let mut header = [0u8; 48];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+    if header[..4] == *b"GGUF" {
+        return Some(
+            "This is a GGUF file, which whisper.cpp can't load; whisper models come as ggml .bin files".to_string(),
+        );
+    }
+    if header[..4] != GGML_MAGIC {
+        return None;
+    }
+    let hparam = |i: usize| i32::from_le_bytes(header[4 + i * 4..8 + i * 4].try_into().unwrap());
+    let n_mels = hparam(9);
+    let ftype = hparam(10) % QUANTIZATION_VERSION_FACTOR;
+    if ftype > MAX_KNOWN_FTYPE {
+        Some(format!(
+            "The model uses weight type {}, newer than this build (whisper-rs {}) supports; \
+             an update of the app, or a model in a common quantization, should load",
+            ftype, WHISPER_RS_VERSION
+        ))
+    } else if n_mels != 80 && n_mels != 128 {
+        Some(format!(
+            "The model expects {} mel bands, which this build (whisper-rs {}) doesn't know",
+            n_mels, WHISPER_RS_VERSION
+        ))
+    } else {
+        None
+    }
+}
+
+/// Warn when a model file's name promises a different size or language coverage than the
+/// loaded weights have, e.g. a large model renamed to "ggml-tiny.bin"
+fn model_name_mismatch(path: &Path, ctx: &WhisperContext) -> Option<String> {
+    let file_name = path.file_name()?.to_string_lossy();
+    let name = file_name.to_lowercase();
+    let actual = ctx.model_type_readable_str().ok()?;
+    let named = ["tiny", "base", "small", "medium", "large", "turbo"]
+        .into_iter()
+        .find(|size| name.contains(size))?;
+    // Turbo is large-v3 with a smaller decoder, which whisper reports as large
+    let named_type = if named == "turbo" { "large" } else { named };
+    let english_only = name.contains(".en");
+    if named_type != actual {
+        Some(format!(
+            "{} is named as a {} model but is actually a {} model",
+            file_name, named, actual
+        ))
+    } else if english_only && ctx.is_multilingual() {
+        Some(format!("{} is named as English-only but is a multilingual model", file_name))
+    } else if !english_only && !ctx.is_multilingual() {
+        Some(format!("{} is an English-only model despite its name", file_name))
+    } else {
+        None
+    }
+}
+
+/// tinydiarize fine-tunes are published with "tdrz" in the file name
+pub fn is_tinydiarize_model(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|n| n.to_string_lossy().to_lowercase().contains("tdrz"))
+}
+
+/// Pick the DTW alignment-head preset from the standard ggml model file names
+fn dtw_preset_for_model(path: &Path) -> Option<DtwModelPreset> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    let english = name.contains(".en");
+    let preset = if name.contains("large-v3-turbo") {
+        DtwModelPreset::LargeV3Turbo
+    } else if name.contains("large-v3") {
+        DtwModelPreset::LargeV3
+    } else if name.contains("large-v2") {
+        DtwModelPreset::LargeV2
+    } else if name.contains("large") {
+        DtwModelPreset::LargeV1
+    } else if name.contains("medium") {
+        if english { DtwModelPreset::MediumEn } else { DtwModelPreset::Medium }
+    } else if name.contains("small") {
+        if english { DtwModelPreset::SmallEn } else { DtwModelPreset::Small }
+    } else if name.contains("base") {
+        if english { DtwModelPreset::BaseEn } else { DtwModelPreset::Base }
+    } else if name.contains("tiny") {
+        if english { DtwModelPreset::TinyEn } else { DtwModelPreset::Tiny }
+    } else {
+        return None;
+    };
+    Some(preset)
+}
+
+fn build_full_params<'a>(
+    options: &'a TranscribeOptions,
+    cancel: &Arc<AtomicBool>,
+    tx: Sender<TranscribeMessage>,
+    stream_segments: bool,
+) -> FullParams<'a, 'a> {
+    let mut params = FullParams::new(SamplingStrategy::Greedy {
+        best_of: options.best_of,
+    });
+    params.set_print_special(options.show_special_tokens);
+    params.set_print_progress(options.print_progress);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_language(Some(options.language.as_deref().unwrap_or("auto")));
+    if let Some(n) = options.n_threads {
+        params.set_n_threads(n);
+    }
+    if !options.initial_prompt.trim().is_empty() {
+        params.set_initial_prompt(options.initial_prompt.trim());
+    }
+    if let Some(ms) = options.duration_ms {
+        params.set_duration_ms(ms);
+    }
+    if let Some(n) = options.max_text_ctx {
+        params.set_n_max_text_ctx(n);
+    }
+    params.set_temperature(options.temperature);
+    params.set_temperature_inc(options.temperature_inc);
+    params.set_single_segment(options.single_segment);
+    params.set_no_context(!options.condition_on_previous);
+    params.set_tdrz_enable(options.tinydiarize);
+    params.set_no_timestamps(options.no_timestamps);
+
+    let cancel = cancel.clone();
+    params.set_abort_callback_safe(move || cancel.load(Ordering::Relaxed));
+
+    let progress_tx = tx.clone();
+    params.set_progress_callback_safe(move |percent: i32| {
+        progress_tx.send(TranscribeMessage::Progress(percent)).ok();
+    });
+
+    if stream_segments {
+        let options = options.clone();
+        params.set_segment_callback_safe_lossy(move |data: SegmentCallbackData| {
+            tx.send(TranscribeMessage::Segment(Segment {
+                start: shift_timestamp(data.start_timestamp, &options),
+                end: shift_timestamp(data.end_timestamp, &options),
+                text: data.text,
+                confidence: 1.0,
+                words: Vec::new(),
+                speaker_turn: false,
+                no_speech_prob: 0.0,
+            }))
+            .ok();
+        });
+    }
+    params
+}
+
+/// Map a timestamp in the audio whisper was given back onto the source file: drop the
+/// lead-in silence, move past the start of the selected range and, if asked, past the cuts
+/// before it (centiseconds)
+fn shift_timestamp(cs: i64, options: &TranscribeOptions) -> i64 {
+    let selection_start = options.selection.map_or(0, |(start, _)| (start * 100.0) as i64);
+    let edited = (cs - options.lead_in_ms as i64 / 10).max(0) + selection_start;
+    if options.cut_times_original {
+        uncut_timestamp(edited, &options.cuts)
+    } else {
+        edited
+    }
+}
+
+/// Cut ranges in centiseconds, sorted, with overlapping ones merged
+fn merged_cuts(cuts: &[(f32, f32)]) -> Vec<(i64, i64)> {
+    let mut ranges: Vec<(i64, i64)> = cuts
+        .iter()
+        .map(|&(start, end)| ((start.max(0.0) * 100.0) as i64, (end * 100.0) as i64))
+        .filter(|(start, end)| end > start)
+        .collect();
+    ranges.sort_unstable();
+    let mut merged: Vec<(i64, i64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// 16 kHz audio with the cut ranges (seconds) taken out
+pub fn remove_cuts(samples: &[f32], cuts: &[(f32, f32)]) -> Vec<f32> {
+    let sample = |cs: i64| (cs as usize * WHISPER_SAMPLE_RATE / 100).min(samples.len());
+    let mut kept = Vec::with_capacity(samples.len());
+    let mut from = 0;
+    for (start, end) in merged_cuts(cuts) {
+        kept.extend_from_slice(&samples[from..sample(start)]);
+        from = sample(end);
+    }
+    kept.extend_from_slice(&samples[from..]);
+    kept
+}
+
+/// A time on the source file moved onto the audio left after the cuts (centiseconds).
+/// A time inside a cut lands where the cut was.
+pub fn cut_timestamp(cs: i64, cuts: &[(f32, f32)]) -> i64 {
+    let mut removed = 0;
+    for (start, end) in merged_cuts(cuts) {
+        if cs <= start {
+            break;
+        }
+        removed += cs.min(end) - start;
+    }
+    cs - removed
+}
+
+/// A time on the audio left after the cuts moved back onto the source file (centiseconds)
+pub fn uncut_timestamp(cs: i64, cuts: &[(f32, f32)]) -> i64 {
+    let mut source = cs;
+    for (start, end) in merged_cuts(cuts) {
+        if source < start {
+            break;
+        }
+        source += end - start;
+    }
+    source
+}
+
+/// Read DTW-aligned token timings from a segment, skipping special tokens
+fn collect_words(segment: &WhisperSegment, token_eot: i32) -> Vec<Word> {
+    let mut words: Vec<Word> = Vec::new();
+    for t in 0..segment.n_tokens() {
+        let Some(token) = segment.get_token(t) else {
+            continue;
+        };
+        if token.token_id() >= token_eot {
+            continue;
+        }
+        let Ok(text) = token.to_str_lossy() else {
+            continue;
+        };
+        let start = token.token_data().t_dtw;
+        if let Some(prev) = words.last_mut() {
+            prev.end = start;
+        }
+        words.push(Word {
+            start,
+            end: segment.end_timestamp(),
+            text: text.into_owned(),
+        });
+    }
+    words
+}
+
+/// Mean probability of the segment's text tokens
+fn segment_confidence(segment: &WhisperSegment, token_eot: i32) -> f32 {
+    let probs: Vec<f32> = (0..segment.n_tokens())
+        .filter_map(|t| segment.get_token(t))
+        .filter(|token| token.token_id() < token_eot)
+        .map(|token| token.token_probability())
+        .collect();
+    if probs.is_empty() {
+        return 1.0;
+    }
+    probs.iter().sum::<f32>() / probs.len() as f32
+}
+
+/// The warning for a `whisper_full` failure that a CPU rerun can get past, or None for errors
+/// the CPU would hit too (bad parameters, no speech to detect a language from).
+/// whisper.cpp returns -7 when it can't allocate the decoder's KV cache; a GPU that fails
+/// partway through the encoder or decoder (-6, -8, -9) may be out of memory too, but it
+/// doesn't say so.
+fn gpu_failure_warning(e: &WhisperError) -> Option<&'static str> {
+    match e {
+        WhisperError::GenericError(-7) => Some("Ran out of GPU memory, finished on CPU instead"),
+        WhisperError::FailedToEncode
+        | WhisperError::FailedToDecode
+        | WhisperError::GenericError(-6 | -8 | -9) => Some("The GPU run failed, finished on CPU instead"),
+        _ => None,
+    }
+}
+
+pub fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Speaking rate over the time inside the segments, so pauses between them don't count.
+/// None for less than a second of speech, where the rate says little.
+pub fn words_per_minute(segments: &[Segment]) -> Option<f32> {
+    let mut words = 0;
+    let mut centiseconds = 0;
+    for segment in segments {
+        let count = segment
+            .text
+            .split_whitespace()
+            .filter(|w| w.chars().any(char::is_alphanumeric))
+            .count();
+        if count > 0 {
+            words += count;
+            centiseconds += (segment.end - segment.start).max(0);
+        }
+    }
+    (words > 0 && centiseconds >= 100).then(|| words as f32 * 6000.0 / centiseconds as f32)
+}
+
+/// Join segment texts into the plain transcript shown in the output box
+pub fn segments_to_text(segments: &[Segment]) -> String {
+    let mut text = String::new();
+    let mut new_speaker = false;
+    for segment in segments {
+        // Each speaker turn starts a new line
+        if new_speaker {
+            text.push('\n');
+            text.push_str(segment.text.trim_start());
+        } else {
+            text.push_str(&segment.text);
+        }
+        new_speaker = segment.speaker_turn;
+    }
+    text.trim().to_string()
+}
+
+/// Lowercase words with punctuation removed, for comparing text loosely
+fn comparable_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// How many closing segments are low-confidence repeats of a known filler phrase
+pub fn trailing_phrase_count(segments: &[Segment], phrases: &[&str]) -> usize {
+    let phrases: Vec<Vec<String>> = phrases
+        .iter()
+        .map(|p| comparable_words(p))
+        .filter(|p| !p.is_empty())
+        .collect();
+    segments
+        .iter()
+        .rev()
+        .take_while(|s| {
+            s.confidence <= TRAILING_PHRASE_MAX_CONFIDENCE && phrases.contains(&comparable_words(&s.text))
+        })
+        .count()
+}
+
+/// Merge adjacent segments until each reaches `min_duration` (centiseconds),
+/// without letting the merged text grow past `max_chars`
+pub fn merge_segments(segments: &[Segment], min_duration: i64, max_chars: usize) -> Vec<Segment> {
+    let mut merged: Vec<Segment> = Vec::new();
+
+    for segment in segments {
+        if let Some(last) = merged.last_mut() {
+            // Never merge across a speaker turn
+            let too_short = last.end - last.start < min_duration && !last.speaker_turn;
+            // Characters, not bytes, so accented and CJK text gets the same limit
+            let chars = |s: &Segment| s.text.trim().chars().count();
+            let fits = chars(last) + 1 + chars(segment) <= max_chars;
+            if too_short && fits {
+                last.end = segment.end;
+                last.text.push_str(&segment.text);
+                last.confidence = last.confidence.min(segment.confidence);
+                last.words.extend_from_slice(&segment.words);
+                last.speaker_turn = segment.speaker_turn;
+                continue;
+            }
+        }
+        merged.push(segment.clone());
+    }
+
+    merged
+}
+
+/// Split segments longer than `max_duration` (centiseconds) at word boundaries, or between
+/// characters in text written without spaces.
+/// Uses DTW word timings when present, otherwise spreads the time by character count.
+pub fn split_long_segments(segments: &[Segment], max_duration: i64) -> Vec<Segment> {
+    let max_duration = max_duration.max(1);
+    let mut split = Vec::new();
+
+    for segment in segments {
+        if segment.end - segment.start <= max_duration {
+            split.push(segment.clone());
+            continue;
+        }
+        let mut parts = if segment.words.is_empty() {
+            split_by_text(segment, max_duration)
+        } else {
+            split_by_words(segment, max_duration)
+        };
+        // The turn comes after the last piece
+        if let Some(last) = parts.last_mut() {
+            last.speaker_turn = segment.speaker_turn;
+        }
+        split.extend(parts);
+    }
+
+    split
+}
+
+fn split_by_words(segment: &Segment, max_duration: i64) -> Vec<Segment> {
+    let mut parts: Vec<Segment> = Vec::new();
+    let mut current: Vec<Word> = Vec::new();
+
+    let flush = |words: &mut Vec<Word>, parts: &mut Vec<Segment>, end: i64| {
+        if words.is_empty() {
+            return;
+        }
+        let start = parts.last().map_or(segment.start, |p: &Segment| p.end);
+        parts.push(Segment {
+            start,
+            end,
+            text: words.iter().map(|w| w.text.as_str()).collect(),
+            confidence: segment.confidence,
+            words: std::mem::take(words),
+            speaker_turn: false,
+            no_speech_prob: segment.no_speech_prob,
+        });
+    };
+
+    for word in &segment.words {
+        let part_start = parts.last().map_or(segment.start, |p| p.end);
+        // Only break before a token that begins a new word
+        if !current.is_empty() && word.text.starts_with(' ') && word.end - part_start > max_duration {
+            let end = word.start;
+            flush(&mut current, &mut parts, end);
+        }
+        current.push(word.clone());
+    }
+    flush(&mut current, &mut parts, segment.end);
+
+    parts
+}
+
+fn split_by_text(segment: &Segment, max_duration: i64) -> Vec<Segment> {
+    let words: Vec<&str> = segment.text.split_whitespace().collect();
+    if words.is_empty() {
+        return vec![segment.clone()];
+    }
+    let duration = segment.end - segment.start;
+    let pieces = ((duration + max_duration - 1) / max_duration) as usize;
+    let total_chars: usize = words.iter().map(|w| w.chars().count() + 1).sum();
+    let target_chars = total_chars.div_ceil(pieces);
+
+    // Runs of text, and whether each begins a word. Words longer than a piece, such as
+    // Chinese or Japanese written without spaces, are broken between characters.
+    let mut runs: Vec<(String, bool)> = Vec::new();
+    for word in words {
+        let chars: Vec<char> = word.chars().collect();
+        for (i, chunk) in chars.chunks(target_chars).enumerate() {
+            runs.push((chunk.iter().collect(), i == 0));
+        }
+    }
+
+    let mut parts: Vec<Segment> = Vec::new();
+    let mut text = String::new();
+    let mut text_chars = 0;
+    let mut chars_done = 0;
+    for (i, (run, starts_word)) in runs.iter().enumerate() {
+        if *starts_word {
+            text.push(' ');
+            text_chars += 1;
+        }
+        text.push_str(run);
+        text_chars += run.chars().count();
+        if text_chars >= target_chars || i + 1 == runs.len() {
+            chars_done += text_chars;
+            text_chars = 0;
+            let start = parts.last().map_or(segment.start, |p| p.end);
+            let end = segment.start + duration * chars_done as i64 / total_chars as i64;
+            parts.push(Segment {
+                start,
+                end: if i + 1 == runs.len() { segment.end } else { end },
+                text: std::mem::take(&mut text),
+                confidence: segment.confidence,
+                words: Vec::new(),
+                speaker_turn: false,
+                no_speech_prob: segment.no_speech_prob,
+            });
+        }
+    }
+
+    parts
+}
+
+/// Format centiseconds as an SRT timestamp (HH:MM:SS,mmm)
+fn format_srt_timestamp(centiseconds: i64) -> String {
+    let ms = centiseconds.max(0) * 10;
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1000) % 60,
+        ms % 1000
+    )
+}
+
+pub fn segments_to_srt(segments: &[Segment]) -> String {
+    let mut srt = String::new();
+    for (i, (segment, (start, end))) in segments.iter().zip(cue_times(segments)).enumerate() {
+        srt.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            format_srt_timestamp(start),
+            format_srt_timestamp(end),
+            segment.text.trim()
+        ));
+    }
+    srt
+}
+
+/// Subtitle timings: segments with DTW word timings start at their first word's aligned time,
+/// which is closer to when it's spoken than whisper's segment timestamp
+fn cue_times(segments: &[Segment]) -> Vec<(i64, i64)> {
+    let mut prev_end = 0;
+    segments
+        .iter()
+        .map(|segment| {
+            let start = segment
+                .words
+                .first()
+                .map(|w| w.start)
+                .filter(|&start| start >= 0 && start < segment.end)
+                .unwrap_or(segment.start)
+                // Never start before the previous cue has ended
+                .max(prev_end.min(segment.start));
+            prev_end = segment.end;
+            (start, segment.end)
+        })
+        .collect()
+}
+
+/// Format centiseconds as a WebVTT timestamp (HH:MM:SS.mmm)
+fn format_vtt_timestamp(centiseconds: i64) -> String {
+    format_srt_timestamp(centiseconds).replace(',', ".")
+}
+
+pub fn segments_to_vtt(segments: &[Segment]) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+    for (segment, (start, end)) in segments.iter().zip(cue_times(segments)) {
+        vtt.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_vtt_timestamp(start),
+            format_vtt_timestamp(end),
+            segment.text.trim()
+        ));
+    }
+    vtt
+}
+
+fn centiseconds_to_secs(cs: i64) -> f64 {
+    cs as f64 / 100.0
+}
+
+/// Segments as JSON with times in the given unit, which is named in the output; word timings
+/// are included when DTW was enabled
+pub fn segments_to_json(segments: &[Segment], unit: TimeUnit) -> String {
+    let segments: Vec<serde_json::Value> = segments.iter().map(|s| segment_json(s, unit)).collect();
+    serde_json::to_string_pretty(&serde_json::json!({
+        "time_unit": unit.name(),
+        "segments": segments,
+    }))
+    .unwrap_or_default()
+}
+
+/// A whole transcript as JSON: the detected language code and any warnings alongside the segments
+pub fn transcript_to_json(transcript: &Transcript) -> String {
+    let segments: Vec<serde_json::Value> = transcript
+        .segments
+        .iter()
+        .map(|s| segment_json(s, TimeUnit::Seconds))
+        .collect();
+    let language = transcript.language.as_deref().and_then(language_code);
+    serde_json::to_string_pretty(&serde_json::json!({
+        "language": language,
+        "time_unit": TimeUnit::Seconds.name(),
+        "warnings": transcript.warnings,
+        "segments": segments,
+    }))
+    .unwrap_or_default()
+}
+
+/// JSON Lines: one compact segment object per line, for log pipelines and appending. Each
+/// line names its time unit, as there's no header to put it in.
+pub fn segments_to_jsonl(segments: &[Segment], unit: TimeUnit) -> String {
+    segments
+        .iter()
+        .map(|s| format!("{}\n", segment_json(s, unit)))
+        .collect()
+}
+
+fn segment_json(s: &Segment, unit: TimeUnit) -> serde_json::Value {
+    // In every record too, so a segment copied out of the file still says what its times mean
+    let mut value = serde_json::json!({
+        "start": unit.json(s.start),
+        "end": unit.json(s.end),
+        "time_unit": unit.name(),
+        "text": s.text.trim(),
+        "confidence": s.confidence,
+    });
+    if s.speaker_turn {
+        value["speaker_turn_next"] = true.into();
+    }
+    if !s.words.is_empty() {
+        value["words"] = s
+            .words
+            .iter()
+            .map(|w| {
+                serde_json::json!({
+                    "start": unit.json(w.start),
+                    "end": unit.json(w.end),
+                    "text": w.text,
+                })
+            })
+            .collect();
+    }
+    value
+}
+
+/// Start a new chapter wherever the speech pauses for at least `min_gap` centiseconds.
+/// Chapters run back to back from the start of the audio, as podcast players expect.
+pub fn chapters_from_gaps(segments: &[Segment], min_gap: i64) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = Vec::new();
+    let mut prev_end = None;
+    for segment in segments.iter().filter(|s| !s.text.trim().is_empty()) {
+        let new_chapter = prev_end.is_none_or(|end| segment.start - end >= min_gap);
+        match chapters.last_mut() {
+            Some(chapter) if !new_chapter => chapter.end = segment.end,
+            _ => chapters.push(Chapter {
+                start: segment.start,
+                end: segment.end,
+                title: chapter_title(&segment.text),
+            }),
+        }
+        prev_end = Some(segment.end);
+    }
+    if let Some(first) = chapters.first_mut() {
+        first.start = 0;
+    }
+    for i in 1..chapters.len() {
+        chapters[i - 1].end = chapters[i].start;
+    }
+    chapters
+}
+
+fn chapter_title(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let title = words[..words.len().min(CHAPTER_TITLE_WORDS)].join(" ");
+    if words.len() > CHAPTER_TITLE_WORDS {
+        format!("{}…", title.trim_end_matches(|c: char| c.is_ascii_punctuation()))
+    } else {
+        title
+    }
+}
+
+/// Podcasting 2.0 JSON chapters, the sidecar file podcast apps read
+pub fn chapters_to_json(chapters: &[Chapter]) -> String {
+    let chapters: Vec<serde_json::Value> = chapters
+        .iter()
+        .map(|c| serde_json::json!({ "startTime": centiseconds_to_secs(c.start), "title": c.title }))
+        .collect();
+    serde_json::to_string_pretty(&serde_json::json!({ "version": "1.2.0", "chapters": chapters }))
+        .unwrap_or_default()
+}
+
+/// FFmpeg metadata listing the chapters, for embedding them into a copy of the audio with
+/// `ffmpeg -i in.mp3 -i chapters.ffmetadata -map_metadata 1 -codec copy out.mp3`
+pub fn chapters_to_ffmetadata(chapters: &[Chapter]) -> String {
+    let mut out = String::from(";FFMETADATA1\n");
+    for chapter in chapters {
+        out.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/100\nSTART={}\nEND={}\ntitle={}\n",
+            chapter.start,
+            chapter.end,
+            escape_ffmetadata(&chapter.title)
+        ));
+    }
+    out
+}
+
+/// Backslash-escape the characters FFmpeg metadata gives a meaning to
+fn escape_ffmetadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Temp files start with this and the process ID, so each instance cleans up only its own
+const TEMP_FILE_PREFIX: &str = "whisper-transcribe";
+
+/// A file for this process in `dir`, or in the system temp folder when that's None
+pub fn temp_path(dir: Option<&Path>, name: &str) -> PathBuf {
+    let dir = dir.map_or_else(std::env::temp_dir, Path::to_path_buf);
+    dir.join(format!("{}-{}-{}", TEMP_FILE_PREFIX, std::process::id(), name))
+}
+
+/// Delete the temp files this process left behind, such as an interrupted download
+pub fn remove_temp_files(dir: Option<&Path>) {
+    let dir = dir.map_or_else(std::env::temp_dir, Path::to_path_buf);
+    let prefix = format!("{}-{}-", TEMP_FILE_PREFIX, std::process::id());
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(&prefix) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Model files (*.bin) in the models folder, sorted by name
+pub fn local_models(dir: &Path) -> Vec<PathBuf> {
+    let mut models: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| is_model_file(p))
+                .collect()
+        })
+        .unwrap_or_default();
+    models.sort();
+    models
+}
+
+/// Transcribe on GPU, streaming to the UI as usual, then again on CPU and
+/// report the segments where the two outputs differ
+pub fn run_verified_transcription(
+    model_path: PathBuf,
+    audio: AudioInput,
+    options: TranscribeOptions,
+    cancel: Arc<AtomicBool>,
+    tx: Sender<TranscribeMessage>,
+) {
+    let (gpu_tx, gpu_rx) = channel();
+    let gpu_run = {
+        let model_path = model_path.clone();
+        let audio = audio.clone();
+        let options = options.clone();
+        let cancel = cancel.clone();
+        thread::spawn(move || run_transcription(model_path, audio, options, cancel, gpu_tx))
+    };
+
+    // Pass everything through except Done, which waits for the CPU run
+    let mut gpu_done = None;
+    let mut on_gpu = false;
+    for msg in gpu_rx {
+        match msg {
+            TranscribeMessage::Done(segments, stats) => gpu_done = Some((segments, stats)),
+            TranscribeMessage::GpuStatus(gpu, device) => {
+                on_gpu = gpu;
+                tx.send(TranscribeMessage::GpuStatus(gpu, device)).ok();
+            }
+            other => {
+                tx.send(other).ok();
+            }
+        }
+    }
+    gpu_run.join().ok();
+    let Some((segments, stats)) = gpu_done else {
+        // Cancelled or failed; the forwarded message already says so
+        return;
+    };
+
+    if !on_gpu {
+        tx.send(TranscribeMessage::Warning(
+            "GPU unavailable, so the CPU verification was skipped".to_string(),
+        ))
+        .ok();
+        tx.send(TranscribeMessage::Done(segments, stats)).ok();
+        return;
+    }
+
+    tx.send(TranscribeMessage::Status("Verifying on CPU...".to_string()))
+        .ok();
+    let (cpu_tx, cpu_rx) = channel();
+    run_transcription(
+        model_path,
+        audio,
+        TranscribeOptions {
+            use_gpu: false,
+            ..options
+        },
+        cancel.clone(),
+        cpu_tx,
+    );
+    let cpu_output = cpu_rx.try_iter().find_map(|msg| match msg {
+        TranscribeMessage::Done(segments, _) => Some(Ok(segments)),
+        TranscribeMessage::Error(e) => Some(Err(e)),
+        _ => None,
+    });
+
+    match cpu_output {
+        Some(Ok(cpu_segments)) => {
+            tx.send(TranscribeMessage::Disagreements(find_disagreements(
+                &segments,
+                &cpu_segments,
+            )))
+            .ok();
+        }
+        Some(Err(e)) => {
+            tx.send(TranscribeMessage::Warning(format!("CPU verification failed: {}", e)))
+                .ok();
+        }
+        None if cancel.load(Ordering::Relaxed) => {
+            tx.send(TranscribeMessage::Warning("CPU verification cancelled".to_string()))
+                .ok();
+        }
+        None => {}
+    }
+    tx.send(TranscribeMessage::Done(segments, stats)).ok();
+}
+
+/// A stretch of audio the GPU and CPU runs transcribed differently (centiseconds)
+#[derive(Clone)]
+pub struct Disagreement {
+    pub start: i64,
+    pub end: i64,
+    pub gpu_text: String,
+    pub cpu_text: String,
+}
+
+/// Pair each CPU segment with the GPU segment containing its midpoint and compare
+/// the words, ignoring case and punctuation
+pub fn find_disagreements(gpu: &[Segment], cpu: &[Segment]) -> Vec<Disagreement> {
+    let mut matched: Vec<Vec<&Segment>> = vec![Vec::new(); gpu.len()];
+    let mut disagreements = Vec::new();
+    for segment in cpu {
+        let mid = (segment.start + segment.end) / 2;
+        match gpu.iter().position(|g| mid >= g.start && mid < g.end.max(g.start + 1)) {
+            Some(i) => matched[i].push(segment),
+            // CPU heard something where the GPU run has no segment
+            None if !comparable_words(&segment.text).is_empty() => disagreements.push(Disagreement {
+                start: segment.start,
+                end: segment.end,
+                gpu_text: String::new(),
+                cpu_text: segment.text.clone(),
+            }),
+            None => {}
+        }
+    }
+
+    for (segment, cpu_segments) in gpu.iter().zip(matched) {
+        let cpu_text: String = cpu_segments.iter().map(|s| s.text.as_str()).collect();
+        if comparable_words(&segment.text) != comparable_words(&cpu_text) {
+            disagreements.push(Disagreement {
+                start: segment.start,
+                end: segment.end,
+                gpu_text: segment.text.clone(),
+                cpu_text,
+            });
+        }
+    }
+    disagreements.sort_by_key(|d| d.start);
+    disagreements
+}
+
+/// Transcribe with the first model, streaming to the UI as usual, then with each of the others,
+/// and finish with the most confident segments of all the runs for each stretch of audio
+pub fn run_ensemble_transcription(
+    model_paths: Vec<PathBuf>,
+    audio: AudioInput,
+    options: TranscribeOptions,
+    cancel: Arc<AtomicBool>,
+    tx: Sender<TranscribeMessage>,
+) {
+    let started = Instant::now();
+    let mut merged: Option<(Vec<Segment>, TranscribeStats)> = None;
+    for (i, model_path) in model_paths.into_iter().enumerate() {
+        let name = model_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if i > 0 {
+            tx.send(TranscribeMessage::Status(format!("Transcribing again with {}...", name)))
+                .ok();
+        }
+        let (run_tx, run_rx) = channel();
+        let run = {
+            let audio = audio.clone();
+            let options = options.clone();
+            let cancel = cancel.clone();
+            thread::spawn(move || run_transcription(model_path, audio, options, cancel, run_tx))
+        };
+        // The first run is passed through as it goes; later ones only report progress and problems
+        let mut done = None;
+        for msg in run_rx {
+            match msg {
+                TranscribeMessage::Done(segments, stats) => done = Some((segments, stats)),
+                // Another model failing still leaves the transcripts so far
+                TranscribeMessage::Error(e) if i > 0 => {
+                    tx.send(TranscribeMessage::Warning(format!("{} failed: {}", name, e)))
+                        .ok();
+                }
+                msg @ (TranscribeMessage::Warning(_)
+                | TranscribeMessage::Progress(_)
+                | TranscribeMessage::Error(_)
+                | TranscribeMessage::Cancelled) => {
+                    tx.send(msg).ok();
+                }
+                msg if i == 0 => {
+                    tx.send(msg).ok();
+                }
+                _ => {}
+            }
+        }
+        run.join().ok();
+        let Some((segments, stats)) = done else {
+            if i > 0 && !cancel.load(Ordering::Relaxed) {
+                continue;
+            }
+            // Cancelled or failed; the forwarded message already says so
+            return;
+        };
+        merged = Some(match merged {
+            None => (segments, stats),
+            Some((best, stats)) => (merge_best_segments(&best, &segments), stats),
+        });
+    }
+    if let Some((segments, stats)) = merged {
+        let stats = TranscribeStats {
+            elapsed_secs: started.elapsed().as_secs_f32(),
+            ..stats
+        };
+        tx.send(TranscribeMessage::Done(segments, stats)).ok();
+    }
+}
+
+/// Combine two transcripts of the same audio. Segments that overlap in time, on either side,
+/// are grouped, so the two models may split the speech at different points; within each group
+/// whichever side whisper was more confident of is kept whole. Speech only `other` found is
+/// kept as well.
+pub fn merge_best_segments(primary: &[Segment], other: &[Segment]) -> Vec<Segment> {
+    let mut all: Vec<(&Segment, bool)> = primary
+        .iter()
+        .map(|s| (s, true))
+        .chain(other.iter().map(|s| (s, false)))
+        .collect();
+    all.sort_by_key(|(s, _)| s.start);
+    // Zero-length segments still take up their instant
+    let end = |s: &Segment| s.end.max(s.start + 1);
+
+    let mut merged = Vec::new();
+    let mut group: Vec<(&Segment, bool)> = Vec::new();
+    let mut group_end = i64::MIN;
+    for (segment, is_primary) in all {
+        if !group.is_empty() && segment.start >= group_end {
+            merged.extend(best_of_group(&group));
+            group.clear();
+        }
+        group_end = if group.is_empty() { end(segment) } else { group_end.max(end(segment)) };
+        group.push((segment, is_primary));
+    }
+    merged.extend(best_of_group(&group));
+    merged
+}
+
+/// The side of an overlapping group with the higher confidence, primary on a tie
+fn best_of_group(group: &[(&Segment, bool)]) -> Vec<Segment> {
+    let side = |primary: bool| group.iter().filter(move |(_, p)| *p == primary).map(|(s, _)| *s);
+    let keep_primary = side(false).next().is_none()
+        || (side(true).next().is_some() && mean_confidence(side(true)) >= mean_confidence(side(false)));
+    side(keep_primary).cloned().collect()
+}
+
+/// Confidence of several segments together, weighting each by its length
+fn mean_confidence<'a>(segments: impl Iterator<Item = &'a Segment>) -> f32 {
+    let (mut sum, mut weight) = (0.0, 0.0);
+    for segment in segments {
+        let length = (segment.end - segment.start).max(1) as f32;
+        sum += segment.confidence * length;
+        weight += length;
+    }
+    if weight > 0.0 { sum / weight } else { 0.0 }
+}
+
+/// Transcribe the built-in benchmark clip with each model in turn, timing load and
+/// transcription together. Every machine runs the same input, so timings compare between them.
+pub fn run_benchmark(
+    models: Vec<PathBuf>,
+    mut options: TranscribeOptions,
+    cancel: Arc<AtomicBool>,
+    tx: Sender<BenchmarkMessage>,
+) {
+    // Ranges picked on the loaded file don't apply to the clip
+    options.selection = None;
+    options.cuts.clear();
+    options.duration_ms = None;
+    let clip = Arc::new(DecodedAudio {
+        samples: test_tone(WHISPER_SAMPLE_RATE as u32, BENCHMARK_SECS),
+        packets: 0,
+        dropped_packets: 0,
+        linear_resampled: false,
+        probed_without_hint: false,
+    });
+    for model_path in models {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let model = model_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        tx.send(BenchmarkMessage::Running(model.clone())).ok();
+
+        let (run_tx, run_rx) = channel();
+        let started = Instant::now();
+        run_transcription(
+            model_path,
+            AudioInput::Decoded(clip.clone()),
+            options.clone(),
+            cancel.clone(),
+            run_tx,
+        );
+        let elapsed_secs = started.elapsed().as_secs_f32();
+
+        let output = run_rx
+            .try_iter()
+            .find_map(|msg| match msg {
+                TranscribeMessage::Done(segments, _) => Some(Ok(segments_to_text(&segments))),
+                TranscribeMessage::Error(e) => Some(Err(e)),
+                _ => None,
+            })
+            .unwrap_or_else(|| Err("Cancelled".to_string()));
+        tx.send(BenchmarkMessage::Result(BenchmarkResult {
+            model,
+            elapsed_secs,
+            output,
+        }))
+        .ok();
+    }
+    tx.send(BenchmarkMessage::Done).ok();
+}
+
+// Length and source rate of the tone the self-test runs through the pipeline
+const SELF_TEST_SECS: usize = 3;
+const SELF_TEST_RATE: u32 = 44100;
+
+// Length of the self-test tone the benchmark times each model on: one full whisper window
+const BENCHMARK_SECS: usize = 30;
+
+/// A 440 Hz tone at half scale
+fn test_tone(sample_rate: u32, secs: usize) -> Vec<f32> {
+    (0..sample_rate as usize * secs)
+        .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin())
+        .collect()
+}
+
+/// Outcome of one step of the self-test: what it checked on success, the error otherwise
+pub struct SelfTestStage {
+    pub name: &'static str,
+    pub result: Result<String, String>,
+}
+
+/// Run a synthetic tone through decoding, resampling and a CPU whisper init, so a failure
+/// can be pinned on the app and model setup rather than on the user's audio file
+pub fn run_self_test(model_path: Option<&Path>, resampler: &ResamplerOptions) -> Vec<SelfTestStage> {
+    let tone = test_tone(SELF_TEST_RATE, SELF_TEST_SECS);
+    let expected = WHISPER_SAMPLE_RATE * SELF_TEST_SECS;
+    let check_length = |samples: usize| {
+        // The sinc filter trims or pads a few samples at the edges
+        if samples.abs_diff(expected) > expected / 100 {
+            Err(format!("Expected about {} samples at 16 kHz, got {}", expected, samples))
+        } else {
+            Ok(format!("{} samples at 16 kHz", samples))
+        }
+    };
+
+    let decode = {
+        let mss = MediaSourceStream::new(
+            Box::new(std::io::Cursor::new(wav_bytes(&tone, SELF_TEST_RATE))),
+            Default::default(),
+        );
+        let mut hint = Hint::new();
+        hint.with_extension("wav");
+        decode_to_mono_16khz(mss, &hint, resampler)
+            .map_err(|e| format!("{:#}", e))
+            .and_then(|audio| check_length(audio.samples.len()))
+    };
+
+    let resample = resample_audio(&tone, SELF_TEST_RATE, WHISPER_SAMPLE_RATE as u32, resampler)
+        .map_err(|e| format!("{:#}", e))
+        .and_then(|samples| check_length(samples.len()));
+
+    let model = match model_path {
+        None => Err("No model selected".to_string()),
+        Some(path) => load_whisper_context(path, false, None)
+            .and_then(|ctx| Ok(ctx.create_state()?))
+            .map(|_| format!("Loaded {} on the CPU", path.display()))
+            .map_err(|e| format!("{:#}", e)),
+    };
+
+    vec![
+        SelfTestStage { name: "Decode", result: decode },
+        SelfTestStage { name: "Resample", result: resample },
+        SelfTestStage { name: "Model", result: model },
+    ]
+}
+
+/// A mono 16-bit PCM WAV file holding `samples`
+fn wav_bytes(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let data_len = samples.len() as u32 * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // PCM, one channel
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    wav
+}
+
+pub fn run_download(
+    model: ModelInfo,
+    dest_path: PathBuf,
+    network: NetworkSettings,
+    temp_dir: Option<PathBuf>,
+    cancel: Arc<AtomicBool>,
+    tx: Sender<DownloadMessage>,
+) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let result = download_model(
+            &model.download_url(),
+            &dest_path,
+            &network,
+            temp_dir.as_deref(),
+            &cancel,
+            &tx,
+        )
+        .await;
+        match result {
+            Ok(path) => {
+                // A truncated or corrupt file can still have the right size; make sure whisper accepts it
+                tx.send(DownloadMessage::Verifying).ok();
+                if let Err(e) = check_against_manifest(&path, &model) {
+                    let _ = std::fs::remove_file(&path);
+                    tx.send(DownloadMessage::Error(e.to_string())).ok();
+                    return;
+                }
+                match load_whisper_context(&path, false, None) {
+                    Ok(_) => {
+                        tx.send(DownloadMessage::Done(path)).ok();
+                    }
+                    Err(e) => {
+                        tx.send(DownloadMessage::Invalid(e.to_string())).ok();
+                    }
+                }
+            }
+            Err(e) => {
+                tx.send(DownloadMessage::Error(e.to_string())).ok();
+            }
+        }
+    });
+}
+
+/// Compare a downloaded model with the size and SHA-256 checksum its list entry gives
+fn check_against_manifest(path: &Path, model: &ModelInfo) -> Result<()> {
+    if let Some(size) = model.size {
+        let actual = std::fs::metadata(path).context("Failed to read the downloaded file")?.len();
+        if actual != size {
+            bail!("The downloaded file is {} bytes, but the model list says {}", actual, size);
+        }
+    }
+    if let Some(ref expected) = model.sha256 {
+        let mut hasher = Sha256::new();
+        let mut file = File::open(path).context("Failed to read the downloaded file")?;
+        std::io::copy(&mut file, &mut hasher).context("Failed to read the downloaded file")?;
+        let actual = format!("{:x}", hasher.finalize());
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            bail!("The downloaded file doesn't match the SHA-256 checksum in the model list");
+        }
+    }
+    Ok(())
+}
+
+const MAX_REDIRECTS: usize = 10;
+
+fn build_http_client(network: &NetworkSettings) -> Result<reqwest::Client> {
+    // reqwest picks up HTTP_PROXY/HTTPS_PROXY on its own unless a proxy is set explicitly
+    // Hugging Face redirects model files to its CDN; a long chain means something is misconfigured
+    let mut builder =
+        reqwest::Client::builder().redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS));
+    let proxy_url = network.proxy_url.trim();
+    if !proxy_url.is_empty() {
+        let mut proxy = reqwest::Proxy::all(proxy_url).context("Invalid proxy URL")?;
+        if !network.proxy_username.is_empty() {
+            proxy = proxy.basic_auth(&network.proxy_username, &network.proxy_password);
+        }
+        builder = builder.proxy(proxy);
+    }
+    if let Some(ref path) = network.ca_cert_path {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read CA certificate {}", path.display()))?;
+        // A PEM file may hold a whole chain; anything else is taken as a single DER certificate
+        let certs = match reqwest::Certificate::from_pem_bundle(&bytes) {
+            Ok(certs) if !certs.is_empty() => certs,
+            _ => vec![reqwest::Certificate::from_der(&bytes).with_context(|| {
+                format!("{} isn't a PEM or DER certificate", path.display())
+            })?],
+        };
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    builder.build().context("Failed to create HTTP client")
+}
+
+// Network chunks are often a few KB; collect them into larger writes to disk
+const DOWNLOAD_WRITE_BUFFER: usize = 1 << 20;
+
+async fn download_model(
+    url: &str,
+    dest_path: &Path,
+    network: &NetworkSettings,
+    temp_dir: Option<&Path>,
+    cancel: &AtomicBool,
+    tx: &Sender<DownloadMessage>,
+) -> Result<PathBuf> {
+    // Ensure models directory exists
+    if let Some(parent) = dest_path.parent() {
+        if parent.is_file() {
+            bail!(
+                "Can't create the models folder because a file named '{}' is in the way. \
+                 Rename or remove that file, or choose a different models folder under Advanced.",
+                parent.display()
+            );
+        }
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create models directory {}", parent.display()))?;
+    }
+
+    let client = build_http_client(network)?;
+    let response = client
+        .get(url)
+        // Ask for the raw file; some mirrors still compress, which is handled below
+        .header(reqwest::header::ACCEPT_ENCODING, "identity")
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_redirect() {
+                anyhow::anyhow!(
+                    "Download failed: {} was redirected more than {} times{}. \
+                     The download link may have moved.",
+                    url,
+                    MAX_REDIRECTS,
+                    e.url().map(|u| format!(" (last to {})", u)).unwrap_or_default()
+                )
+            } else {
+                anyhow::Error::new(e).context("Failed to start download")
+            }
+        })?;
+
+    let final_url = response.url().clone();
+    let host = final_url.host_str().unwrap_or_default().to_string();
+    tx.send(DownloadMessage::Source {
+        host: host.clone(),
+        url: final_url.to_string(),
+    })
+    .ok();
+
+    if !response.status().is_success() {
+        bail!("Download failed: HTTP {} from {}", response.status(), host);
+    }
+
+    // An error or login page served with a 200 would otherwise be saved as the model
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_lowercase())
+        .unwrap_or_default();
+    if content_type.starts_with("text/") || content_type.contains("json") || content_type.contains("xml") {
+        bail!(
+            "Download failed: {} sent a web page ({}) instead of the model file. {}",
+            host,
+            content_type.split(';').next().unwrap_or_default(),
+            NOT_A_MODEL_HINT
+        );
+    }
+
+    let encoding = response
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| v != "identity");
+
+    // Content-Length counts the bytes on the wire, so progress tracks received (possibly compressed) bytes
+    let total_size = response.content_length().unwrap_or(0);
+    let mut downloaded: u64 = 0;
+
+    // Written to the temp folder first, so a partial file never shows up among the models
+    let file_name = dest_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let part_path = temp_path(temp_dir, &format!("{}.part", file_name));
+    let dest_dir = dest_path.parent().unwrap_or(Path::new("."));
+    let part_dir = part_path.parent().unwrap_or(Path::new("."));
+    if total_size > 0 {
+        for dir in [part_dir, dest_dir] {
+            if let Ok(available) = fs2::available_space(dir) {
+                if available < total_size {
+                    bail!(not_enough_space_message(total_size, available, dir));
+                }
+            }
+        }
+    }
+
+    let file = File::create(&part_path)
+        .with_context(|| format!("Failed to create {}", part_path.display()))?;
+    let file = BufWriter::with_capacity(DOWNLOAD_WRITE_BUFFER, file);
+    let mut writer: Box<dyn Write> = match encoding.as_deref() {
+        None => Box::new(file),
+        Some("gzip" | "x-gzip") => Box::new(flate2::write::GzDecoder::new(file)),
+        Some("deflate") => Box::new(flate2::write::ZlibDecoder::new(file)),
+        Some(other) => {
+            drop(file);
+            let _ = std::fs::remove_file(&part_path);
+            bail!("Server sent the model with unsupported encoding '{}'", other);
+        }
+    };
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        if cancel.load(Ordering::Relaxed) {
+            drop(writer);
+            let _ = std::fs::remove_file(&part_path);
+            bail!("Download cancelled");
+        }
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                drop(writer);
+                let _ = std::fs::remove_file(&part_path);
+                return Err(anyhow::Error::new(e).context("Error downloading chunk"));
+            }
+        };
+        if let Err(e) = writer.write_all(&chunk) {
+            drop(writer);
+            return Err(write_failed(e, &part_path, total_size));
+        }
+        downloaded += chunk.len() as u64;
+        tx.send(DownloadMessage::Progress {
+            downloaded,
+            total: total_size,
+        })
+        .ok();
+    }
+    // Flushes the decoder and the buffer, so write errors surface here rather than on drop
+    if let Err(e) = writer.flush() {
+        drop(writer);
+        return Err(write_failed(e, &part_path, total_size));
+    }
+    drop(writer);
+
+    // A dropped connection can end the stream early without an error
+    if total_size > 0 && downloaded != total_size {
+        let _ = std::fs::remove_file(&part_path);
+        bail!(
+            "Download incomplete: received {} of {} bytes",
+            downloaded,
+            total_size
+        );
+    }
+
+    // Servers don't always label error pages, so check the file itself too
+    if !has_ggml_magic(&part_path) {
+        let _ = std::fs::remove_file(&part_path);
+        bail!(
+            "Download failed: {} sent something that isn't a whisper model. {}",
+            host,
+            NOT_A_MODEL_HINT
+        );
+    }
+
+    if let Err(e) = move_file(&part_path, dest_path) {
+        let _ = std::fs::remove_file(&part_path);
+        let context = format!("Failed to move the download into {}", dest_dir.display());
+        return Err(anyhow::Error::new(e).context(context));
+    }
+
+    // Get absolute path for the model
+    let abs_path = dest_path
+        .canonicalize()
+        .unwrap_or_else(|_| dest_path.to_path_buf());
+    Ok(abs_path)
+}
+
+const NOT_A_MODEL_HINT: &str = "This model may be gated or the URL is wrong.";
+
+// First four bytes of every whisper.cpp model file: 0x67676d6c ("ggml") stored little-endian
+const GGML_MAGIC: [u8; 4] = *b"lmgg";
+
+fn has_ggml_magic(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok_and(|()| magic == GGML_MAGIC)
+}
+
+/// Rename a file, copying it instead when the destination is on another drive
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+/// Remove the partial model after a failed write, calling out a full disk
+fn write_failed(e: std::io::Error, dest_path: &Path, total_size: u64) -> anyhow::Error {
+    let _ = std::fs::remove_file(dest_path);
+    if e.kind() == std::io::ErrorKind::StorageFull {
+        let dir = dest_path.parent().unwrap_or(Path::new("."));
+        // The partial file is gone now, so what's free is what the whole model can use
+        let available = fs2::available_space(dir).unwrap_or(0);
+        return anyhow::anyhow!(not_enough_space_message(total_size, available, dir));
+    }
+    anyhow::Error::new(e).context("Failed to write to file")
+}
+
+fn not_enough_space_message(needed: u64, available: u64, dir: &Path) -> String {
+    let needed = if needed > 0 {
+        format!("{:.1} MB", needed as f64 / 1_000_000.0)
+    } else {
+        "an unknown amount".to_string()
+    };
+    format!(
+        "Not enough disk space: the model needs {} but only {:.1} MB is free in {}",
+        needed,
+        available as f64 / 1_000_000.0,
+        dir.display()
+    )
+}
+
+/// Length of an audio file in seconds from its headers, without decoding it.
+/// None when the container doesn't record it (e.g. MP3 without a Xing header).
+pub fn probe_duration_secs(path: &Path) -> Option<f64> {
+    let file = File::open(path).ok()?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .ok()?;
+    let track = probed
+        .format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)?;
+    let frames = track.codec_params.n_frames?;
+    let rate = track.codec_params.sample_rate?;
+    Some(frames as f64 / rate as f64)
+}
+
+/// Probe an audio file for its title/artist tags without decoding it
+pub fn read_audio_tags(path: &PathBuf) -> Result<AudioTags> {
+    let file = File::open(path).context("Failed to open audio file")?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mut probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .context("Unsupported audio format")?;
+
+    let mut tags = AudioTags::default();
+
+    // Tags found while probing (e.g. ID3v2 ahead of the stream), then tags inside the container
+    if let Some(metadata) = probed.metadata.get() {
+        if let Some(revision) = metadata.current() {
+            collect_tags(revision.tags(), &mut tags);
+        }
+    }
+    let metadata = probed.format.metadata();
+    if let Some(revision) = metadata.current() {
+        collect_tags(revision.tags(), &mut tags);
+    }
+
+    Ok(tags)
+}
+
+fn collect_tags(found: &[Tag], tags: &mut AudioTags) {
+    for tag in found {
+        match tag.std_key {
+            Some(StandardTagKey::TrackTitle) if tags.title.is_none() => {
+                tags.title = Some(tag.value.to_string());
+            }
+            Some(StandardTagKey::Artist) if tags.artist.is_none() => {
+                tags.artist = Some(tag.value.to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replace characters that aren't allowed in file names on common platforms
+pub fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Mono 16kHz samples plus how many packets had to be skipped while decoding
+pub struct DecodedAudio {
+    pub samples: Vec<f32>,
+    pub packets: usize,
+    pub dropped_packets: usize,
+    // A sample rate the sinc resampler rejected was converted by linear interpolation instead
+    pub linear_resampled: bool,
+    // The format wasn't found from the file extension, only by probing the content without it
+    pub probed_without_hint: bool,
+}
+
+impl DecodedAudio {
+    /// True when enough packets were undecodable that the transcript is likely missing parts
+    pub fn is_partial(&self) -> bool {
+        self.dropped_packets > 0
+            && self.dropped_packets * 100 >= self.packets.max(1) * MAX_DROPPED_PACKET_PERCENT
+    }
+}
+
+/// Load any supported audio file and convert to mono 16kHz f32 samples
+pub fn load_audio_to_mono_16khz(
+    path: &PathBuf,
+    resampler: &ResamplerOptions,
+    mmap: bool,
+) -> Result<DecodedAudio> {
+    // Opened again for each probe, which consumes the stream
+    let open = || -> Result<MediaSourceStream> {
+        let file = File::open(path).context("Failed to open audio file")?;
+        Ok(if mmap {
+            // SAFETY: the map is read-only; another process truncating the file while
+            // it's decoded is the usual mmap caveat and is accepted for this opt-in mode.
+            let map = unsafe { memmap2::Mmap::map(&file) }.context("Failed to memory-map audio file")?;
+            MediaSourceStream::new(Box::new(std::io::Cursor::new(map)), Default::default())
+        } else {
+            MediaSourceStream::new(Box::new(file), Default::default())
+        })
+    };
+
+    // Create a hint based on file extension
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let decoded = match probe_source(open()?, &hint) {
+        Ok(probed) => decode_probed(probed, resampler),
+        // A wrong extension can point the probe at the wrong format; the content alone may still be recognized
+        Err(e) if path.extension().is_some() => match probe_source(open()?, &Hint::new()) {
+            Ok(probed) => decode_probed(probed, resampler).map(|decoded| DecodedAudio {
+                probed_without_hint: true,
+                ..decoded
+            }),
+            Err(_) => Err(e),
+        },
+        Err(e) => Err(e),
+    };
+    decoded.map_err(|e| {
+        // Encrypted store purchases fail with an opaque codec or decode error
+        if is_drm_protected(path) {
+            anyhow::anyhow!("This file appears to be DRM-protected and cannot be transcribed.")
+        } else {
+            e
+        }
+    })
+}
+
+/// Look for the encryption markers MP4 (FairPlay) and ASF (WMA DRM) files carry in their headers
+fn is_drm_protected(path: &Path) -> bool {
+    const MARKERS: &[&[u8]] = &[
+        // MP4 protection scheme info and encrypted/FairPlay sample entries
+        b"sinf",
+        b"drms",
+        b"enca",
+        // ASF content encryption objects (GUIDs as stored on disk)
+        &[0xFB, 0xB3, 0x11, 0x22, 0x23, 0xBD, 0xD2, 0x11, 0xB4, 0xB7, 0x00, 0xA0, 0xC9, 0x55, 0xFC, 0x6E],
+        &[0x14, 0xE6, 0x8A, 0x29, 0x22, 0x26, 0x17, 0x4C, 0xB9, 0x35, 0xDA, 0xE0, 0x7E, 0xE9, 0x28, 0x9C],
+    ];
+    // Headers sit at the start of store files, so a few MB is plenty
+    let mut header = Vec::new();
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    if file.take(4 << 20).read_to_end(&mut header).is_err() {
+        return false;
+    }
+    MARKERS
+        .iter()
+        .any(|marker| header.windows(marker.len()).any(|w| w == *marker))
+}
+
+/// Read a piped audio stream (e.g. WAV from ffmpeg) from stdin and convert to mono 16kHz
+pub fn load_stdin_to_mono_16khz(resampler: &ResamplerOptions) -> Result<DecodedAudio> {
+    let source = ReadOnlySource::new(std::io::stdin());
+    let mss = MediaSourceStream::new(Box::new(source), Default::default());
+    decode_to_mono_16khz(mss, &Hint::new(), resampler)
+}
+
+fn decode_to_mono_16khz(
+    mss: MediaSourceStream,
+    hint: &Hint,
+    resampler: &ResamplerOptions,
+) -> Result<DecodedAudio> {
+    decode_probed(probe_source(mss, hint)?, resampler)
+}
+
+/// Identify the container format of a media source
+fn probe_source(mss: MediaSourceStream, hint: &Hint) -> Result<ProbeResult> {
+    symphonia::default::get_probe()
+        .format(hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .context("Unsupported audio format")
+}
+
+fn decode_probed(probed: ProbeResult, resampler: &ResamplerOptions) -> Result<DecodedAudio> {
+    let mut format = probed.format;
+
+    // Find the first audio track
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
+        .context("No audio track found")?;
+
+    let track_id = track.id;
+
+    // Create decoder
+    let decoder_opts = DecoderOptions::default();
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &decoder_opts)
+        .context("Failed to create decoder")?;
+
+    // Resample on a worker while this thread keeps decoding; the bounded channel
+    // keeps the decoder from racing ahead of the resampler on long files
+    let (chunk_tx, chunk_rx) = sync_channel::<(u32, Vec<f32>)>(DECODE_QUEUE_CHUNKS);
+    let resampler = *resampler;
+    let worker = thread::spawn(move || resample_runs(chunk_rx, &resampler));
+
+    let mut packets = 0;
+    let mut dropped_packets = 0;
+    let mut decoded_samples = 0;
+
+    // Mono samples go to the worker tagged with their rate; some WebM/Opus captures change rate mid-stream
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(symphonia::core::errors::Error::IoError(ref e))
+                if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break;
+            }
+            Err(e) => bail!("Error reading packet: {}", e),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+        packets += 1;
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Damaged packets are skipped, but counted so partial decodes can be reported
+            Err(symphonia::core::errors::Error::DecodeError(_)) => {
+                dropped_packets += 1;
+                continue;
+            }
+            Err(e) => bail!("Decode error: {}", e),
+        };
+
+        let spec = *decoded.spec();
+        let duration = decoded.capacity() as usize;
+        let channels = spec.channels.count().max(1);
+
+        let mut sample_buf = SampleBuffer::<f32>::new(duration as u64, spec);
+        sample_buf.copy_interleaved_ref(decoded);
+
+        // Convert to mono if stereo/multi-channel
+        let mono: Vec<f32> = if channels > 1 {
+            sample_buf
+                .samples()
+                .chunks(channels)
+                .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
+                .collect()
+        } else {
+            sample_buf.samples().to_vec()
+        };
+        decoded_samples += mono.len();
+        if chunk_tx.send((spec.rate, mono)).is_err() {
+            // The worker stopped early; its error is reported below
+            break;
+        }
+    }
+    drop(chunk_tx);
+
+    let (samples, linear_resampled) = worker
+        .join()
+        .map_err(|_| anyhow::anyhow!("Resampler thread panicked"))??;
+    if decoded_samples == 0 {
+        bail!("No audio samples decoded");
+    }
+
+    Ok(DecodedAudio {
+        samples,
+        packets,
+        dropped_packets,
+        linear_resampled,
+        probed_without_hint: false,
+    })
+}
+
+// Decoded packets buffered between the decoder and the resampler
+const DECODE_QUEUE_CHUNKS: usize = 64;
+
+// Input frames the streaming resampler processes at a time
+const RESAMPLE_CHUNK_FRAMES: usize = 4096;
+
+/// Resample incoming mono chunks to 16kHz, starting a new resampler whenever the rate changes.
+/// Also says whether any run fell back to linear interpolation.
+fn resample_runs(
+    chunks: Receiver<(u32, Vec<f32>)>,
+    options: &ResamplerOptions,
+) -> Result<(Vec<f32>, bool)> {
+    let target_rate = WHISPER_SAMPLE_RATE as u32;
+    let mut samples = Vec::new();
+    let mut linear = false;
+    let mut current: Option<(u32, Option<ChunkedResampler>)> = None;
+
+    for (rate, chunk) in chunks {
+        if current.as_ref().is_none_or(|(r, _)| *r != rate) {
+            if let Some((_, Some(run))) = current.take() {
+                samples.extend(run.finish()?);
+            }
+            let resampler = if rate == target_rate {
+                None
+            } else {
+                let run = ChunkedResampler::new(rate, target_rate, options)?;
+                linear |= run.is_linear();
+                Some(run)
+            };
+            current = Some((rate, resampler));
+        }
+        match current {
+            Some((_, Some(ref mut run))) => run.push(&chunk)?,
+            _ => samples.extend(chunk),
+        }
+    }
+    if let Some((_, Some(run))) = current {
+        samples.extend(run.finish()?);
+    }
+    Ok((samples, linear))
+}
+
+/// Sinc resampler fed a stream of samples in fixed-size chunks
+struct ChunkedResampler {
+    // None when rubato rejected the ratio; the input is then interpolated linearly at the end
+    resampler: Option<SincFixedIn<f32>>,
+    // Input not yet making up a whole chunk
+    pending: Vec<f32>,
+    output: Vec<f32>,
+    input_len: usize,
+    ratio: f64,
+}
+
+impl ChunkedResampler {
+    fn new(from_rate: u32, to_rate: u32, options: &ResamplerOptions) -> Result<Self> {
+        let params = SincInterpolationParameters {
+            sinc_len: 256,
+            f_cutoff: options.f_cutoff,
+            interpolation: SincInterpolationType::Linear,
+            oversampling_factor: options.oversampling_factor,
+            window: options.window.to_rubato(),
+        };
+        if from_rate == 0 {
+            bail!("Invalid sample rate: 0 Hz");
+        }
+        let ratio = to_rate as f64 / from_rate as f64;
+        // Some unusual rates give ratios rubato won't take; a cruder conversion still transcribes
+        let resampler = SincFixedIn::<f32>::new(ratio, 2.0, params, RESAMPLE_CHUNK_FRAMES, 1).ok();
+        Ok(Self {
+            resampler,
+            pending: Vec::new(),
+            output: Vec::new(),
+            input_len: 0,
+            ratio,
+        })
+    }
+
+    fn is_linear(&self) -> bool {
+        self.resampler.is_none()
+    }
+
+    fn push(&mut self, samples: &[f32]) -> Result<()> {
+        self.input_len += samples.len();
+        self.pending.extend_from_slice(samples);
+        let Some(ref mut resampler) = self.resampler else {
+            return Ok(());
+        };
+        let mut used = 0;
+        while self.pending.len() - used >= RESAMPLE_CHUNK_FRAMES {
+            let out = resampler
+                .process(&[&self.pending[used..used + RESAMPLE_CHUNK_FRAMES]], None)
+                .context("Failed to resample audio")?;
+            self.output.extend_from_slice(&out[0]);
+            used += RESAMPLE_CHUNK_FRAMES;
+        }
+        self.pending.drain(..used);
+        Ok(())
+    }
+
+    /// Flush the filter and return the output, cut to the input's duration
+    fn finish(mut self) -> Result<Vec<f32>> {
+        let expected = (self.input_len as f64 * self.ratio).ceil() as usize;
+        let Some(ref mut resampler) = self.resampler else {
+            return Ok(resample_linear(&self.pending, self.ratio, expected));
+        };
+        if !self.pending.is_empty() {
+            let out = resampler
+                .process_partial(Some(&[&self.pending[..]]), None)
+                .context("Failed to resample audio")?;
+            self.output.extend_from_slice(&out[0]);
+        }
+        while self.output.len() < expected {
+            let out = resampler
+                .process_partial::<&[f32]>(None, None)
+                .context("Failed to resample audio")?;
+            if out[0].is_empty() {
+                break;
+            }
+            self.output.extend_from_slice(&out[0]);
+        }
+        self.output.truncate(expected);
+        Ok(self.output)
+    }
+}
+
+/// Plain linear interpolation, used only when the sinc resampler can't be built. Without an
+/// anti-aliasing filter, downsampling lets some high frequencies fold back into speech.
+fn resample_linear(samples: &[f32], ratio: f64, len: usize) -> Vec<f32> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    (0..len)
+        .map(|i| {
+            let pos = i as f64 / ratio;
+            let idx = (pos as usize).min(samples.len() - 1);
+            let next = (idx + 1).min(samples.len() - 1);
+            let frac = (pos - idx as f64).min(1.0) as f32;
+            samples[idx] + (samples[next] - samples[idx]) * frac
+        })
+        .collect()
+}
+
+/// Overall RMS level of the samples in dBFS
+fn rms_dbfs(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+    let mean_square = samples.iter().map(|s| (s * s) as f64).sum::<f64>() / samples.len() as f64;
+    10.0 * mean_square.log10() as f32
+}
+
+/// Boost quiet windows towards a common level, capped by each window's peak so nothing clips.
+/// The gain at the edge between two windows is the smaller of their boosts, and it ramps to a
+/// window's own boost at its centre, so a loud passage is never lifted by a quiet one beside it.
+/// A pause takes the boost of the speech next to it, so the ramp up happens in the pause
+/// rather than over the first or last words.
+pub fn apply_auto_gain(samples: &mut [f32], strength: f32) {
+    if strength <= 0.0 || samples.is_empty() {
+        return;
+    }
+    // Boost for each speech window, None for silence, and the most any window can take
+    let windows: Vec<(Option<f32>, f32)> = samples
+        .chunks(AUTO_GAIN_WINDOW)
+        .map(|window| {
+            let peak = window.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+            let headroom = (-20.0 * peak.log10()).max(0.0);
+            let level = rms_dbfs(window);
+            let boost = (level >= AUTO_GAIN_NOISE_FLOOR_DBFS).then(|| {
+                ((AUTO_GAIN_TARGET_DBFS - level) * strength.min(1.0))
+                    .clamp(0.0, AUTO_GAIN_MAX_BOOST_DB)
+                    .min(headroom)
+            });
+            (boost, headroom)
+        })
+        .collect();
+    let boosts: Vec<f32> = (0..windows.len())
+        .map(|i| match windows[i] {
+            (Some(boost), _) => boost,
+            (None, headroom) => {
+                let before = i.checked_sub(1).and_then(|j| windows[j].0);
+                let after = windows.get(i + 1).and_then(|w| w.0);
+                before.into_iter().chain(after).fold(0.0, f32::max).min(headroom)
+            }
+        })
+        .collect();
+
+    if boosts.iter().all(|&db| db == 0.0) {
+        return;
+    }
+    let last = boosts.len() - 1;
+    for (i, sample) in samples.iter_mut().enumerate() {
+        let window = i / AUTO_GAIN_WINDOW;
+        let centre = boosts[window];
+        let frac = (i % AUTO_GAIN_WINDOW) as f32 / AUTO_GAIN_WINDOW as f32;
+        let db = if frac < 0.5 {
+            let edge = centre.min(boosts[window.saturating_sub(1)]);
+            edge + (centre - edge) * frac * 2.0
+        } else {
+            let edge = centre.min(boosts[(window + 1).min(last)]);
+            centre + (edge - centre) * (frac - 0.5) * 2.0
+        };
+        *sample *= 10f32.powf(db / 20.0);
+    }
+}
+
+/// High-quality resampling using rubato
+pub fn resample_audio(
+    samples: &[f32],
+    from_rate: u32,
+    to_rate: u32,
+    options: &ResamplerOptions,
+) -> Result<Vec<f32>> {
+    let mut resampler = ChunkedResampler::new(from_rate, to_rate, options)?;
+    resampler.push(samples)?;
+    let resampled = resampler.finish()?;
+    if resampled.is_empty() && !samples.is_empty() {
+        bail!(
+            "Resampling from {} Hz to {} Hz produced no output",
+            from_rate,
+            to_rate
+        );
+    }
+    Ok(resampled)
+}
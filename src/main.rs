@@ -1,42 +1,101 @@
 #![windows_subsystem = "windows"]
 
+mod batch;
+mod i18n;
+mod playback;
+#[cfg(feature = "serve")]
+mod serve;
+mod single_instance;
+
 use anyhow::{Context, Result, bail};
+use batch::{
+    combined_document, failure_report, format_duration, BatchEstimate, BatchItem, BatchProgress, BatchSample,
+    BatchStatus, CombinedFormat,
+};
 use eframe::egui;
-use futures_util::StreamExt;
-use rubato::{Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction};
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
-use std::sync::mpsc::{channel, Receiver, Sender};
+use i18n::{tr, UiLanguage};
+use playback::Player;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Arc;
 use std::thread;
-use symphonia::core::audio::SampleBuffer;
-use symphonia::core::codecs::DecoderOptions;
-use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::MediaSourceStream;
-use symphonia::core::meta::MetadataOptions;
-use symphonia::core::probe::Hint;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
-
-// Available Whisper models
-const WHISPER_MODELS: &[(&str, &str)] = &[
-    ("tiny", "ggml-tiny.bin"),
-    ("base", "ggml-base.bin"),
-    ("small", "ggml-small.bin"),
-    ("medium", "ggml-medium.bin"),
-    ("large", "ggml-large-v3-turbo.bin"),
-];
-
-fn get_model_url(filename: &str) -> String {
-    format!(
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/{}",
-        filename
-    )
+use std::time::{Duration, Instant, SystemTime};
+use whisper_transcribe::align;
+use whisper_transcribe::normalize::fix_capitalization;
+use whisper_transcribe::normalize::normalize_text;
+use whisper_transcribe::normalize::TextLanguage;
+use whisper_transcribe::*;
+
+/// Default model suggestion: small runs comfortably on a GPU, base is the sweet spot on CPU
+fn recommended_model_idx(models: &[ModelInfo], gpu_available: bool) -> usize {
+    let name = if gpu_available { "small" } else { "base" };
+    models.iter().position(|m| m.name == name).unwrap_or(0)
+}
+
+// Below this window width the layout switches to compact mode
+const COMPACT_WIDTH: f32 = 480.0;
+
+/// A row of controls that, in compact mode, wraps onto further lines instead of running off the edge
+fn control_row<R>(
+    ui: &mut egui::Ui,
+    compact: bool,
+    add_contents: impl FnOnce(&mut egui::Ui) -> R,
+) -> egui::InnerResponse<R> {
+    if compact {
+        ui.horizontal_wrapped(add_contents)
+    } else {
+        ui.horizontal(add_contents)
+    }
 }
 
-// Supported audio formats
-const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "flac", "ogg", "m4a", "aac", "wma", "opus", "webm"];
+// Number of peak bars drawn in the waveform preview
+const WAVEFORM_BUCKETS: usize = 800;
+
+// How long the Copy button's "Copied!" / error note stays visible
+const COPY_FEEDBACK_DURATION: Duration = Duration::from_secs(3);
+
+const CLI_USAGE: &str = "Usage: whisper-transcribe --model <file.bin> [--language <code or name>] \
+[--output-dir <dir> [--format txt|srt|vtt|json|jsonl] [--time-unit s|ms|cs] [--name <template>]] \
+(<audio file> | --stdin-wav)
+       whisper-transcribe --model <file.bin> [--language <code or name>] --serve [--port <port>] \
+[--temp-dir <dir>]
+Name templates may use {stem}, {model}, {lang} and {ext}.";
+
+// Port for --serve when --port isn't given
+const DEFAULT_SERVE_PORT: u16 = 8178;
+
+/// Extensions for a file dialog filter in both cases, since some platforms'
+/// pickers match them case-sensitively and recorders often write ".MP3"
+fn dialog_extensions(extensions: &[&str]) -> Vec<String> {
+    extensions
+        .iter()
+        .flat_map(|e| [e.to_lowercase(), e.to_uppercase()])
+        .collect()
+}
+
+/// Interpret pasted text as the path of an existing audio file
+fn audio_path_from_text(text: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(text.trim().trim_matches('"'));
+    (is_audio_file(&path) && path.is_file()).then_some(path)
+}
 
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // Only audio files, as the file manager passes them for "Open with": queue them in
+    // the window that's already open, or in a new one
+    let files: Vec<PathBuf> = if args.iter().all(|a| is_audio_file(Path::new(a)) && Path::new(a).is_file()) {
+        args.iter().map(PathBuf::from).collect()
+    } else {
+        // Any other arguments switch to headless CLI mode
+        std::process::exit(run_cli(&args));
+    };
+    if !files.is_empty() && single_instance::send_to_running(&files) {
+        return Ok(());
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([600.0, 500.0])
@@ -45,61 +104,604 @@ fn main() -> eframe::Result<()> {
     };
 
     eframe::run_native(
-        "Whisper Transcribe",
+        APP_NAME,
         options,
-        Box::new(|_cc| Ok(Box::new(WhisperApp::default()))),
+        Box::new(|cc| {
+            let mut app = WhisperApp::new(cc);
+            app.queue_incoming_files(files);
+            app.incoming_files = single_instance::listen(cc.egui_ctx.clone());
+            Ok(Box::new(app))
+        }),
     )
 }
 
-#[derive(PartialEq)]
+struct CliArgs {
+    model_path: PathBuf,
+    // None when serving
+    audio: Option<AudioInput>,
+    // Serve transcriptions over HTTP on this port instead of transcribing one input
+    serve_port: Option<u16>,
+    options: TranscribeOptions,
+    // Save to a file here instead of printing to stdout
+    output_dir: Option<PathBuf>,
+    format: String,
+    // Timestamp unit for json and jsonl output
+    time_unit: TimeUnit,
+    name_template: String,
+    // Where uploads to the server are written; None uses the system temp folder
+    temp_dir: Option<PathBuf>,
+}
+
+fn parse_cli_args(args: &[String]) -> Result<CliArgs> {
+    let mut model_path = None;
+    let mut audio = None;
+    let mut options = TranscribeOptions {
+        print_progress: true,
+        ..Default::default()
+    };
+    let mut output_dir = None;
+    let mut format = "txt".to_string();
+    let mut time_unit = TimeUnit::Seconds;
+    let mut name_template = DEFAULT_OUTPUT_TEMPLATE.to_string();
+    let mut serve = false;
+    let mut port = DEFAULT_SERVE_PORT;
+    let mut temp_dir = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--model" => model_path = Some(PathBuf::from(iter.next().context("--model needs a path")?)),
+            "--language" => {
+                let lang = iter.next().context("--language needs a code or name")?;
+                options.language = if lang == "auto" {
+                    None
+                } else {
+                    Some(language_code(lang).with_context(|| format!("Unknown language: {}", lang))?)
+                };
+            }
+            "--stdin-wav" => audio = Some(AudioInput::Stdin),
+            "--output-dir" => {
+                output_dir = Some(PathBuf::from(iter.next().context("--output-dir needs a path")?))
+            }
+            "--format" => {
+                format = iter.next().context("--format needs txt, srt, vtt, json or jsonl")?.to_lowercase();
+                if !EXPORT_FORMATS.contains(&format.as_str()) {
+                    bail!("Unknown format: {}", format);
+                }
+            }
+            "--time-unit" => {
+                let value = iter.next().context("--time-unit needs s, ms or cs")?;
+                time_unit =
+                    TimeUnit::from_name(value).with_context(|| format!("Unknown time unit: {}", value))?;
+            }
+            "--name" => name_template = iter.next().context("--name needs a template")?.clone(),
+            "--serve" => serve = true,
+            "--port" => {
+                let value = iter.next().context("--port needs a number")?;
+                port = value.parse().with_context(|| format!("Invalid port: {}", value))?;
+            }
+            "--temp-dir" => temp_dir = Some(PathBuf::from(iter.next().context("--temp-dir needs a path")?)),
+            a if a.starts_with("--") => bail!("Unknown option: {}", a),
+            a => audio = Some(AudioInput::File(PathBuf::from(a))),
+        }
+    }
+
+    Ok(CliArgs {
+        model_path: model_path.context("Missing --model")?,
+        audio: if serve { None } else { Some(audio.context("Missing audio input")?) },
+        serve_port: serve.then_some(port),
+        options,
+        output_dir,
+        format,
+        time_unit,
+        name_template,
+        temp_dir,
+    })
+}
+
+/// The Windows build is a GUI app with no console of its own; borrow the one it was started
+/// from so the CLI's stdout and stderr show up there. Redirected output is unaffected.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // SAFETY: no pointers are passed; failing (started from Explorer, say) leaves nothing to undo
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// Transcribe without the GUI, printing the text to stdout. Returns the process exit code.
+fn run_cli(args: &[String]) -> i32 {
+    #[cfg(windows)]
+    attach_parent_console();
+    let cli = match parse_cli_args(args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}\n{}", e, CLI_USAGE);
+            return 2;
+        }
+    };
+
+    if let Some(port) = cli.serve_port {
+        return run_server(&cli.model_path, port, &cli.options, cli.temp_dir.as_deref());
+    }
+    let Some(audio) = cli.audio else {
+        eprintln!("Missing audio input\n{}", CLI_USAGE);
+        return 2;
+    };
+
+    let stem = match audio {
+        AudioInput::File(ref path) => file_stem_or(path, "transcription"),
+        _ => "stdin".to_string(),
+    };
+    let model = file_stem_or(&cli.model_path, "model");
+    let mut lang = cli.options.language.clone().unwrap_or_else(|| "auto".to_string());
+
+    let (tx, rx) = channel();
+    let cancel = Arc::new(AtomicBool::new(false));
+    run_transcription(cli.model_path, audio, cli.options, cancel, tx);
+
+    for msg in rx.try_iter() {
+        match msg {
+            TranscribeMessage::DetectedLanguage(name) => {
+                if let Some(code) = language_code(&name) {
+                    lang = code;
+                }
+            }
+            TranscribeMessage::Done(segments, _) => {
+                let Some(ref dir) = cli.output_dir else {
+                    println!("{}", segments_to_text(&segments));
+                    return 0;
+                };
+                let contents = match cli.format.as_str() {
+                    "srt" => segments_to_srt(&segments),
+                    "vtt" => segments_to_vtt(&segments),
+                    "json" => segments_to_json(&segments, cli.time_unit),
+                    "jsonl" => segments_to_jsonl(&segments, cli.time_unit),
+                    _ => segments_to_text(&segments),
+                };
+                let saved = expand_output_template(&cli.name_template, &stem, &model, &lang, &cli.format)
+                    .and_then(|name| {
+                        let path = dir.join(name);
+                        std::fs::write(&path, contents)
+                            .with_context(|| format!("Failed to write {}", path.display()))?;
+                        Ok(path)
+                    });
+                return match saved {
+                    Ok(path) => {
+                        println!("{}", path.display());
+                        0
+                    }
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        1
+                    }
+                };
+            }
+            TranscribeMessage::Warning(w) => eprintln!("Warning: {}", w),
+            TranscribeMessage::Error(e) => {
+                eprintln!("Error: {}", e);
+                return 1;
+            }
+            _ => {}
+        }
+    }
+    1
+}
+
+/// Run the local HTTP service until it's stopped. Returns the process exit code.
+#[cfg(feature = "serve")]
+fn run_server(model: &Path, port: u16, options: &TranscribeOptions, temp_dir: Option<&Path>) -> i32 {
+    match serve::serve(model, port, options, temp_dir) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(not(feature = "serve"))]
+fn run_server(_model: &Path, _port: u16, _options: &TranscribeOptions, _temp_dir: Option<&Path>) -> i32 {
+    eprintln!("Error: this build has no server mode; rebuild with --features serve");
+    2
+}
+
+#[derive(Clone, PartialEq)]
 enum TranscribeStatus {
     Idle,
     Loading,
     Transcribing,
+    // Re-running on CPU to check the GPU output
+    Verifying,
     Done,
+    Cancelled,
     Error(String),
 }
 
+/// How the transcript is shown
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum OutputView {
+    Text,
+    // One focusable line per segment, for keyboard and screen-reader use
+    List,
+    // Editable segments with play buttons
+    Edit,
+}
+
 #[derive(PartialEq, Clone)]
 enum DownloadStatus {
     Idle,
     Downloading { downloaded: u64, total: u64 },
+    // Test-loading the finished file before it's selected
+    Verifying,
     Done,
     Error(String),
+    // The download finished but whisper couldn't load the file
+    Invalid(String),
 }
 
-enum DownloadMessage {
-    Progress { downloaded: u64, total: u64 },
-    Done(PathBuf),
-    Error(String),
+/// Peak levels of the loaded audio, for the waveform preview
+struct Waveform {
+    peaks: Vec<f32>,
+    duration_secs: f32,
+}
+
+/// Output removed by Clear, kept so the last clear can be undone
+struct ClearedOutput {
+    segments: Vec<Segment>,
+    original_text: Vec<String>,
+    status: TranscribeStatus,
+    warning: Option<String>,
+    stats: Option<TranscribeStats>,
+}
+
+const SETTINGS_KEY: &str = "settings";
+
+// Window title, and the name eframe keeps the app's settings under
+const APP_NAME: &str = "Whisper Transcribe";
+
+// Progress of a running batch, kept next to the settings so it outlives a crash
+const BATCH_PROGRESS_FILE: &str = "batch_progress.json";
+
+fn batch_progress_path() -> Option<PathBuf> {
+    eframe::storage_dir(APP_NAME).map(|dir| dir.join(BATCH_PROGRESS_FILE))
+}
+
+/// Everything remembered between sessions, stored via eframe's persistence
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct Settings {
+    model_path: Option<PathBuf>,
+    models_dir: PathBuf,
+    temp_dir: Option<PathBuf>,
+    options: TranscribeOptions,
+    // Tuned options per model, keyed by model file name
+    model_options: HashMap<String, TranscribeOptions>,
+    model_speed: HashMap<String, f32>,
+    profiles: BTreeMap<String, Profile>,
+    notify_on_done: bool,
+    merge_segments: bool,
+    merge_min_duration: f32,
+    merge_max_chars: usize,
+    max_segment_duration: Option<f32>,
+    chapter_gap: f32,
+    min_confidence: Option<f32>,
+    mark_low_confidence: bool,
+    normalize_text: bool,
+    fix_capitalization: bool,
+    trim_trailing_phrases: bool,
+    trailing_phrases: String,
+    output_template: String,
+    line_ending: LineEnding,
+    json_time_unit: TimeUnit,
+    output_view: OutputView,
+    show_segment_wpm: bool,
+    show_debug_info: bool,
+    ensemble_model: Option<String>,
+    post_command_enabled: bool,
+    post_command: String,
+    delete_audio_after_save: bool,
+    delete_audio_confirmed: bool,
+    batch_individual_files: bool,
+    batch_format: String,
+    batch_combined: bool,
+    batch_combined_format: CombinedFormat,
+    theme: egui::ThemePreference,
+    ui_language: UiLanguage,
+    network: NetworkSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        WhisperApp::default().settings()
+    }
+}
+
+/// Transcription and output settings written to a file so a team can share them. Paths and
+/// machine-specific choices (GPU, threads, folders, theme) stay out.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct SharedSettings {
+    // Model file name, looked up in the importer's models folder
+    model: Option<String>,
+    options: TranscribeOptions,
+    merge_segments: bool,
+    merge_min_duration: f32,
+    merge_max_chars: usize,
+    max_segment_duration: Option<f32>,
+    chapter_gap: f32,
+    min_confidence: Option<f32>,
+    mark_low_confidence: bool,
+    normalize_text: bool,
+    fix_capitalization: bool,
+    trim_trailing_phrases: bool,
+    trailing_phrases: String,
+    output_template: String,
+    line_ending: LineEnding,
+    json_time_unit: TimeUnit,
+    batch_format: String,
+}
+
+impl Default for SharedSettings {
+    fn default() -> Self {
+        WhisperApp::default().shared_settings()
+    }
+}
+
+/// A named set of transcription settings the user can switch between
+#[derive(Clone, Serialize, Deserialize)]
+struct Profile {
+    model_path: Option<PathBuf>,
+    options: TranscribeOptions,
+}
+
+// Environment variable that carries the saved file's path to post-processing commands on Windows
+#[cfg(windows)]
+const POST_COMMAND_FILE_VAR: &str = "WHISPER_TRANSCRIBE_FILE";
+
+/// Run a post-processing command through the system shell without waiting for it, with
+/// `{file}` standing for the saved file. The path never becomes part of the command text, as
+/// names taken from audio files and tags could otherwise run as shell code: `sh` gets it as
+/// `$1`, `cmd` through an environment variable.
+fn spawn_post_command(template: &str, file: &Path) -> std::io::Result<std::process::Child> {
+    // The placeholder is quoted below, so quotes the user put around it are dropped
+    let template = template.replace("\"{file}\"", "{file}").replace("'{file}'", "{file}");
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // Passed as is, since Rust's argument quoting isn't what cmd expects; /S strips just the
+        // outer quotes, leaving any inside the command alone
+        let command = template.replace("{file}", &format!("\"%{}%\"", POST_COMMAND_FILE_VAR));
+        std::process::Command::new("cmd")
+            .raw_arg(format!("/S /C \"{}\"", command))
+            .env(POST_COMMAND_FILE_VAR, file)
+            .spawn()
+    }
+    #[cfg(not(windows))]
+    {
+        let command = template.replace("{file}", "\"$1\"");
+        std::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .arg("sh")
+            .arg(file)
+            .spawn()
+    }
+}
+
+/// Absolute form of a path for tooltips, falling back to the path as given
+fn full_path(path: &Path) -> String {
+    std::path::absolute(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+/// Key used to remember per-model options
+fn model_key(path: &Path) -> Option<String> {
+    path.file_name().map(|n| n.to_string_lossy().into_owned())
+}
+
+/// Position of a model in the list by its file name, or else by the model name in it, so ".en"
+/// and custom builds of a listed size match too
+fn model_list_idx(models: &[ModelInfo], path: &Path) -> Option<usize> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    models
+        .iter()
+        .position(|m| m.filename.to_lowercase() == name)
+        .or_else(|| models.iter().position(|m| name.contains(&m.name.to_lowercase())))
+}
+
+/// Decoded audio kept so re-transcribing regions of the same file skips decoding
+struct AudioCache {
+    path: PathBuf,
+    // The file's modified time when it was decoded
+    modified: Option<SystemTime>,
+    resampler: ResamplerOptions,
+    audio: Arc<DecodedAudio>,
+}
+
+impl AudioCache {
+    /// Whether the cached samples still match the file on disk and the resampler settings
+    fn is_current(&self, path: &Path, resampler: &ResamplerOptions) -> bool {
+        self.path == path
+            && self.resampler == *resampler
+            && self.modified.is_some()
+            && self.modified == file_modified(path)
+    }
 }
 
-/// Check if CUDA is available by attempting to initialize whisper with GPU
-fn check_cuda_available() -> bool {
-    // We can't easily check without a model, so we'll detect during first transcription
-    // For now, check if CUDA environment is set up
-    std::env::var("CUDA_PATH").is_ok()
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
 }
 
 struct WhisperApp {
     model_path: Option<PathBuf>,
     audio_path: Option<PathBuf>,
+    // When more than one file is picked, all parts in order (audio_path is the first)
+    audio_parts: Vec<PathBuf>,
+    audio_tags: AudioTags,
+    waveform: Option<Waveform>,
+    waveform_receiver: Option<Receiver<Result<(Waveform, AudioCache), String>>>,
+    audio_cache: Option<AudioCache>,
+    // The same unchanged file was picked again, so its decoded audio is being reused
+    audio_reused: bool,
+    // Range to transcribe (seconds) and where the current drag began
+    audio_selection: Option<(f32, f32)>,
+    selection_anchor: Option<f32>,
+    // Ranges (seconds) left out of the transcription, such as intros or ad breaks
+    audio_cuts: Vec<(f32, f32)>,
+    // Cuts the shown segments' times leave out; empty when they're times on the source file
+    run_cuts: Vec<(f32, f32)>,
     transcription: String,
+    segments: Vec<Segment>,
+    // Whisper's text for each segment, so edits can be marked and reverted
+    original_text: Vec<String>,
     status: TranscribeStatus,
+    // Non-fatal issue from the last run, shown next to the status
+    transcribe_warning: Option<String>,
+    // Throughput of the last completed run
+    last_stats: Option<TranscribeStats>,
+    // Percent of the audio processed so far, once whisper reports it
+    progress: Option<i32>,
+    cleared: Option<ClearedOutput>,
+    // Transcript fills the window, hiding the other controls
+    output_maximized: bool,
+    output_view: OutputView,
+    // Speaking rate after each line of the segment list, to spot rushed passages
+    show_segment_wpm: bool,
+    // Extra numbers in the segment list for tuning, such as whisper's no-speech probability
+    show_debug_info: bool,
+    // Second model (file name in the models folder) whose more confident segments replace the main run's
+    ensemble_model: Option<String>,
+    // Segment last clicked or focused in the list view
+    selected_segment: Option<usize>,
+    // Segments ticked in the list view (indices into output_segments), to export just those
+    checked_segments: BTreeSet<usize>,
+    // Audio output, opened the first time a segment is played
+    player: Option<Player>,
+    // Index of the segment being played
+    playing_segment: Option<usize>,
+    // Segments where the GPU and CPU runs disagree; None when the run wasn't verified
+    disagreements: Option<Vec<Disagreement>>,
+    // Outcome of the last Copy, shown briefly next to the buttons
+    copy_feedback: Option<(Result<(), String>, Instant)>,
     receiver: Option<Receiver<TranscribeMessage>>,
+    transcribe_cancel: Arc<AtomicBool>,
+    // Re-run of one segment's audio, spliced back in place of that segment when done
+    splice_receiver: Option<Receiver<TranscribeMessage>>,
+    splice_segment: usize,
+    // Cues of an imported SRT, re-timed against the transcript when the current run finishes
+    retime_cues: Option<Vec<Segment>>,
     using_gpu: Option<bool>,
+    // Backend and device name reported by ggml, e.g. "CUDA: RTX 4070"
+    gpu_device: Option<String>,
+    detected_language: Option<String>,
     // Download state
+    models_dir: PathBuf,
+    // Where downloads are staged; None uses the system temp folder
+    temp_dir: Option<PathBuf>,
     selected_model_idx: usize,
+    // Models offered for download, from models.json in the models folder or the built-in list
+    models: Vec<ModelInfo>,
+    // Why the models folder's models.json was ignored
+    model_list_warning: Option<String>,
     download_status: DownloadStatus,
     download_receiver: Option<Receiver<DownloadMessage>>,
-    cuda_available: bool,
-}
-
-enum TranscribeMessage {
-    Status(String),
-    GpuStatus(bool),
-    Done(String),
-    Error(String),
+    download_started: Option<Instant>,
+    // Host and final URL the model is served from, after redirects
+    download_source: Option<(String, String)>,
+    download_cancel: Arc<AtomicBool>,
+    // Start transcribing as soon as the in-flight download completes
+    transcribe_after_download: bool,
+    // Model benchmark state
+    benchmark_receiver: Option<Receiver<BenchmarkMessage>>,
+    benchmark_cancel: Arc<AtomicBool>,
+    benchmark_running: Option<String>,
+    benchmark_results: Vec<BenchmarkResult>,
+    // Pipeline self-test: decode, resample and model init on a synthetic tone
+    self_test_receiver: Option<Receiver<Vec<SelfTestStage>>>,
+    self_test_results: Vec<SelfTestStage>,
+    // Batch queue: files transcribed one after another into batch_output_dir
+    batch: Vec<BatchItem>,
+    batch_receiver: Option<Receiver<TranscribeMessage>>,
+    batch_cancel: Arc<AtomicBool>,
+    batch_output_dir: Option<PathBuf>,
+    // Outcome of the last batch, shown under the queue
+    batch_note: Option<String>,
+    // Source files removed by "Delete audio after successful transcription" in this batch
+    batch_deleted: usize,
+    // Files, length and expected time of the batch about to start, awaiting confirmation
+    batch_estimate: Option<BatchEstimate>,
+    batch_estimate_receiver: Option<Receiver<BatchEstimate>>,
+    // Narrow window: the download section folds away and control rows wrap
+    compact: bool,
+    // How "Sample" thins out the waiting files in the queue
+    batch_sample: BatchSample,
+    // A batch left unfinished by a crash or by closing the app, offered for resuming at startup
+    unfinished_batch: Option<BatchProgress>,
+    // Files sent by later launches of the app, added to the batch queue
+    incoming_files: Option<Receiver<PathBuf>>,
+    // Save each file's transcript in batch_format, and/or one document with all of them
+    batch_individual_files: bool,
+    batch_format: String,
+    batch_combined: bool,
+    batch_combined_format: CombinedFormat,
+    gpu_available: bool,
+    // Shown on first launch, before any settings have been saved
+    show_setup_wizard: bool,
+    options: TranscribeOptions,
+    model_options: HashMap<String, TranscribeOptions>,
+    // Measured speed per model file, in seconds of audio per second of processing
+    model_speed: HashMap<String, f32>,
+    profiles: BTreeMap<String, Profile>,
+    selected_profile: Option<String>,
+    new_profile_name: String,
+    notify_on_done: bool,
+    // Subtitle export
+    merge_segments: bool,
+    merge_min_duration: f32,
+    merge_max_chars: usize,
+    // Split subtitles longer than this many seconds; None leaves them as whisper made them
+    max_segment_duration: Option<f32>,
+    // Pause (seconds) that starts a new chapter in saved chapter files
+    chapter_gap: f32,
+    // Segments below this confidence are dropped (or marked); None keeps everything
+    min_confidence: Option<f32>,
+    mark_low_confidence: bool,
+    // Rule-based cleanup of numbers and punctuation spacing
+    normalize_text: bool,
+    // Capitalize sentence starts (and "I" in English) for models that write in lowercase
+    fix_capitalization: bool,
+    // Drop low-confidence closing segments matching one of these phrases (one per line)
+    trim_trailing_phrases: bool,
+    trailing_phrases: String,
+    // File name for automatically saved output, e.g. "{stem}_{model}.{ext}"
+    output_template: String,
+    line_ending: LineEnding,
+    // Timestamp unit in JSON and JSON Lines exports
+    json_time_unit: TimeUnit,
+    // Shell command run on each saved transcript, with {file} replaced by its path (opt-in)
+    post_command_enabled: bool,
+    post_command: String,
+    // Remove the source audio once its transcript is saved (opt-in, confirmed once)
+    delete_audio_after_save: bool,
+    delete_audio_confirmed: bool,
+    confirm_delete_audio: bool,
+    // Language an English-only model was about to be asked for, pending confirmation
+    language_mismatch: Option<String>,
+    // Audio the current transcript came from, cleared once it's been deleted
+    transcribed_sources: Vec<PathBuf>,
+    source_deleted: bool,
+    // System follows the OS light/dark setting (dark if it can't be detected)
+    theme: egui::ThemePreference,
+    ui_language: UiLanguage,
+    network: NetworkSettings,
 }
 
 impl Default for WhisperApp {
@@ -107,246 +709,3373 @@ impl Default for WhisperApp {
         Self {
             model_path: None,
             audio_path: None,
+            audio_parts: Vec::new(),
+            audio_tags: AudioTags::default(),
+            waveform: None,
+            waveform_receiver: None,
+            audio_cache: None,
+            audio_reused: false,
+            audio_selection: None,
+            audio_cuts: Vec::new(),
+            run_cuts: Vec::new(),
+            selection_anchor: None,
             transcription: String::new(),
+            segments: Vec::new(),
+            original_text: Vec::new(),
             status: TranscribeStatus::Idle,
+            transcribe_warning: None,
+            last_stats: None,
+            progress: None,
+            cleared: None,
+            output_maximized: false,
+            output_view: OutputView::Text,
+            show_segment_wpm: false,
+            show_debug_info: false,
+            ensemble_model: None,
+            selected_segment: None,
+            checked_segments: BTreeSet::new(),
+            player: None,
+            playing_segment: None,
+            disagreements: None,
+            copy_feedback: None,
             receiver: None,
+            splice_receiver: None,
+            splice_segment: 0,
+            retime_cues: None,
+            transcribe_cancel: Arc::new(AtomicBool::new(false)),
             using_gpu: None,
+            gpu_device: None,
+            detected_language: None,
+            models_dir: PathBuf::from("models"),
+            temp_dir: None,
             selected_model_idx: 0,
+            models: builtin_models(),
+            model_list_warning: None,
             download_status: DownloadStatus::Idle,
             download_receiver: None,
-            cuda_available: check_cuda_available(),
+            download_started: None,
+            download_source: None,
+            download_cancel: Arc::new(AtomicBool::new(false)),
+            transcribe_after_download: false,
+            benchmark_receiver: None,
+            benchmark_cancel: Arc::new(AtomicBool::new(false)),
+            benchmark_running: None,
+            benchmark_results: Vec::new(),
+            self_test_receiver: None,
+            self_test_results: Vec::new(),
+            batch: Vec::new(),
+            batch_receiver: None,
+            batch_cancel: Arc::new(AtomicBool::new(false)),
+            batch_output_dir: None,
+            batch_note: None,
+            batch_deleted: 0,
+            batch_estimate: None,
+            batch_estimate_receiver: None,
+            compact: false,
+            batch_sample: BatchSample::EveryNth(10),
+            unfinished_batch: None,
+            incoming_files: None,
+            batch_individual_files: true,
+            batch_format: "txt".to_string(),
+            batch_combined: false,
+            batch_combined_format: CombinedFormat::Text,
+            gpu_available: check_gpu_available(),
+            show_setup_wizard: false,
+            options: TranscribeOptions::default(),
+            model_options: HashMap::new(),
+            model_speed: HashMap::new(),
+            profiles: BTreeMap::new(),
+            selected_profile: None,
+            new_profile_name: String::new(),
+            notify_on_done: false,
+            merge_segments: false,
+            merge_min_duration: 1.5,
+            merge_max_chars: 80,
+            max_segment_duration: None,
+            chapter_gap: 3.0,
+            min_confidence: None,
+            mark_low_confidence: false,
+            normalize_text: false,
+            fix_capitalization: false,
+            trim_trailing_phrases: true,
+            trailing_phrases: DEFAULT_TRAILING_PHRASES.to_string(),
+            output_template: DEFAULT_OUTPUT_TEMPLATE.to_string(),
+            line_ending: LineEnding::Native,
+            json_time_unit: TimeUnit::Seconds,
+            post_command_enabled: false,
+            post_command: String::new(),
+            delete_audio_after_save: false,
+            delete_audio_confirmed: false,
+            confirm_delete_audio: false,
+            language_mismatch: None,
+            transcribed_sources: Vec::new(),
+            source_deleted: false,
+            theme: egui::ThemePreference::System,
+            ui_language: UiLanguage::default(),
+            network: NetworkSettings::default(),
         }
     }
 }
 
 impl WhisperApp {
-    fn start_transcription(&mut self) {
-        let model_path = self.model_path.clone().unwrap();
-        let audio_path = self.audio_path.clone().unwrap();
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::default();
+        match cc
+            .storage
+            .and_then(|storage| eframe::get_value::<Settings>(storage, SETTINGS_KEY))
+        {
+            Some(settings) => {
+                app.apply_settings(settings);
+                app.reload_model_list();
+            }
+            None => {
+                app.reload_model_list();
+                app.show_setup_wizard = true;
+                app.selected_model_idx = recommended_model_idx(&app.models, app.gpu_available);
+            }
+        }
+        cc.egui_ctx.set_theme(app.theme);
+        app.unfinished_batch = batch_progress_path()
+            .and_then(|path| BatchProgress::load(&path))
+            .filter(|progress| progress.remaining() > 0);
+        app
+    }
 
-        let (tx, rx) = channel();
-        self.receiver = Some(rx);
-        self.status = TranscribeStatus::Loading;
-        self.transcription.clear();
+    fn apply_settings(&mut self, settings: Settings) {
+        self.model_path = settings.model_path.filter(|p| p.exists());
+        self.models_dir = settings.models_dir;
+        self.temp_dir = settings.temp_dir;
+        self.options = settings.options;
+        self.model_options = settings.model_options;
+        self.model_speed = settings.model_speed;
+        self.profiles = settings.profiles;
+        self.notify_on_done = settings.notify_on_done;
+        self.merge_segments = settings.merge_segments;
+        self.merge_min_duration = settings.merge_min_duration;
+        self.merge_max_chars = settings.merge_max_chars;
+        self.max_segment_duration = settings.max_segment_duration;
+        self.chapter_gap = settings.chapter_gap;
+        self.min_confidence = settings.min_confidence;
+        self.mark_low_confidence = settings.mark_low_confidence;
+        self.normalize_text = settings.normalize_text;
+        self.fix_capitalization = settings.fix_capitalization;
+        self.trim_trailing_phrases = settings.trim_trailing_phrases;
+        self.trailing_phrases = settings.trailing_phrases;
+        self.output_template = settings.output_template;
+        self.line_ending = settings.line_ending;
+        self.json_time_unit = settings.json_time_unit;
+        self.output_view = settings.output_view;
+        self.show_segment_wpm = settings.show_segment_wpm;
+        self.show_debug_info = settings.show_debug_info;
+        self.ensemble_model = settings.ensemble_model;
+        self.post_command_enabled = settings.post_command_enabled;
+        self.post_command = settings.post_command;
+        self.delete_audio_after_save = settings.delete_audio_after_save;
+        self.delete_audio_confirmed = settings.delete_audio_confirmed;
+        self.batch_individual_files = settings.batch_individual_files;
+        self.batch_format = settings.batch_format;
+        self.batch_combined = settings.batch_combined;
+        self.batch_combined_format = settings.batch_combined_format;
+        self.theme = settings.theme;
+        self.ui_language = settings.ui_language;
+        self.network = settings.network;
+    }
 
-        thread::spawn(move || {
-            run_transcription(model_path, audio_path, tx);
-        });
+    fn settings(&self) -> Settings {
+        let mut model_options = self.model_options.clone();
+        if let Some(key) = self.model_path.as_deref().and_then(model_key) {
+            model_options.insert(key, self.options.clone());
+        }
+        Settings {
+            model_path: self.model_path.clone(),
+            models_dir: self.models_dir.clone(),
+            temp_dir: self.temp_dir.clone(),
+            options: self.options.clone(),
+            model_options,
+            model_speed: self.model_speed.clone(),
+            profiles: self.profiles.clone(),
+            notify_on_done: self.notify_on_done,
+            merge_segments: self.merge_segments,
+            merge_min_duration: self.merge_min_duration,
+            merge_max_chars: self.merge_max_chars,
+            max_segment_duration: self.max_segment_duration,
+            chapter_gap: self.chapter_gap,
+            min_confidence: self.min_confidence,
+            mark_low_confidence: self.mark_low_confidence,
+            normalize_text: self.normalize_text,
+            fix_capitalization: self.fix_capitalization,
+            trim_trailing_phrases: self.trim_trailing_phrases,
+            trailing_phrases: self.trailing_phrases.clone(),
+            output_template: self.output_template.clone(),
+            line_ending: self.line_ending,
+            json_time_unit: self.json_time_unit,
+            output_view: self.output_view,
+            show_segment_wpm: self.show_segment_wpm,
+            show_debug_info: self.show_debug_info,
+            ensemble_model: self.ensemble_model.clone(),
+            post_command_enabled: self.post_command_enabled,
+            post_command: self.post_command.clone(),
+            delete_audio_after_save: self.delete_audio_after_save,
+            delete_audio_confirmed: self.delete_audio_confirmed,
+            batch_individual_files: self.batch_individual_files,
+            batch_format: self.batch_format.clone(),
+            batch_combined: self.batch_combined,
+            batch_combined_format: self.batch_combined_format,
+            theme: self.theme,
+            ui_language: self.ui_language,
+            network: self.network.clone(),
+        }
     }
 
-    fn check_messages(&mut self) {
-        let mut should_clear_receiver = false;
+    fn shared_settings(&self) -> SharedSettings {
+        SharedSettings {
+            model: self.model_path.as_deref().and_then(model_key),
+            options: self.options.clone(),
+            merge_segments: self.merge_segments,
+            merge_min_duration: self.merge_min_duration,
+            merge_max_chars: self.merge_max_chars,
+            max_segment_duration: self.max_segment_duration,
+            chapter_gap: self.chapter_gap,
+            min_confidence: self.min_confidence,
+            mark_low_confidence: self.mark_low_confidence,
+            normalize_text: self.normalize_text,
+            fix_capitalization: self.fix_capitalization,
+            trim_trailing_phrases: self.trim_trailing_phrases,
+            trailing_phrases: self.trailing_phrases.clone(),
+            output_template: self.output_template.clone(),
+            line_ending: self.line_ending,
+            json_time_unit: self.json_time_unit,
+            batch_format: self.batch_format.clone(),
+        }
+    }
 
-        if let Some(ref receiver) = self.receiver {
-            while let Ok(msg) = receiver.try_recv() {
-                match msg {
-                    TranscribeMessage::Status(s) => {
-                        if s.contains("Transcribing") {
-                            self.status = TranscribeStatus::Transcribing;
-                        }
-                    }
-                    TranscribeMessage::GpuStatus(gpu) => {
-                        self.using_gpu = Some(gpu);
-                    }
-                    TranscribeMessage::Done(text) => {
-                        self.transcription = text;
-                        self.status = TranscribeStatus::Done;
-                        should_clear_receiver = true;
-                    }
-                    TranscribeMessage::Error(e) => {
-                        self.status = TranscribeStatus::Error(e);
-                        should_clear_receiver = true;
-                    }
+    /// Take over imported settings, keeping this machine's GPU, thread and file reading choices
+    fn apply_shared_settings(&mut self, shared: SharedSettings) {
+        if let Some(model) = shared.model {
+            let path = self.models_dir.join(&model);
+            if path.exists() {
+                self.set_model_path(path);
+            } else {
+                if let Some(idx) = model_list_idx(&self.models, Path::new(&model)) {
+                    self.selected_model_idx = idx;
                 }
+                self.transcribe_warning = Some(format!(
+                    "The imported settings use {}, which isn't in the models folder yet.",
+                    model
+                ));
             }
         }
+        let local = self.options.clone();
+        self.options = shared.options;
+        self.options.use_gpu = local.use_gpu;
+        self.options.n_threads = local.n_threads;
+        self.options.mmap_audio = local.mmap_audio;
+        self.options.gpu_timeout_secs = local.gpu_timeout_secs;
+        self.merge_segments = shared.merge_segments;
+        self.merge_min_duration = shared.merge_min_duration;
+        self.merge_max_chars = shared.merge_max_chars;
+        self.max_segment_duration = shared.max_segment_duration;
+        self.chapter_gap = shared.chapter_gap;
+        self.min_confidence = shared.min_confidence;
+        self.mark_low_confidence = shared.mark_low_confidence;
+        self.normalize_text = shared.normalize_text;
+        self.fix_capitalization = shared.fix_capitalization;
+        self.trim_trailing_phrases = shared.trim_trailing_phrases;
+        self.trailing_phrases = shared.trailing_phrases;
+        self.output_template = shared.output_template;
+        self.line_ending = shared.line_ending;
+        self.json_time_unit = shared.json_time_unit;
+        self.batch_format = shared.batch_format;
+        self.selected_profile = None;
+        self.refresh_transcription();
+    }
 
-        if should_clear_receiver {
-            self.receiver = None;
+    fn export_settings(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Settings (JSON)", &["json"])
+            .set_file_name("whisper-transcribe-settings.json")
+            .save_file()
+        else {
+            return;
+        };
+        let contents = serde_json::to_string_pretty(&self.shared_settings()).unwrap_or_default();
+        if let Err(e) = std::fs::write(&path, contents) {
+            self.transcribe_warning = Some(format!("Couldn't save {}: {}", path.display(), e));
         }
     }
 
-    fn start_download(&mut self) {
-        let (_, filename) = WHISPER_MODELS[self.selected_model_idx];
-        let url = get_model_url(filename);
-        let dest_path = PathBuf::from("models").join(filename);
-
-        let (tx, rx) = channel();
-        self.download_receiver = Some(rx);
-        self.download_status = DownloadStatus::Downloading { downloaded: 0, total: 0 };
+    fn import_settings(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Settings (JSON)", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        let shared = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()));
+        match shared {
+            Ok(shared) => self.apply_shared_settings(shared),
+            Err(e) => {
+                self.transcribe_warning =
+                    Some(format!("Couldn't read settings from {}: {}", path.display(), e))
+            }
+        }
+    }
 
-        thread::spawn(move || {
-            run_download(url, dest_path, tx);
-        });
+    fn apply_profile(&mut self, name: &str) {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            return;
+        };
+        if let Some(path) = profile.model_path.filter(|p| p.exists()) {
+            self.set_model_path(path);
+        }
+        self.options = profile.options;
+        self.selected_profile = Some(name.to_string());
     }
 
-    fn check_download_messages(&mut self) {
-        let mut should_clear_receiver = false;
-        let mut completed_path: Option<PathBuf> = None;
+    fn save_profile(&mut self, name: String) {
+        self.profiles.insert(
+            name.clone(),
+            Profile {
+                model_path: self.model_path.clone(),
+                options: self.options.clone(),
+            },
+        );
+        self.selected_profile = Some(name);
+    }
 
-        if let Some(ref receiver) = self.download_receiver {
-            while let Ok(msg) = receiver.try_recv() {
-                match msg {
-                    DownloadMessage::Progress { downloaded, total } => {
-                        self.download_status = DownloadStatus::Downloading { downloaded, total };
-                    }
-                    DownloadMessage::Done(path) => {
-                        self.download_status = DownloadStatus::Done;
-                        completed_path = Some(path);
-                        should_clear_receiver = true;
-                    }
-                    DownloadMessage::Error(e) => {
-                        self.download_status = DownloadStatus::Error(e);
-                        should_clear_receiver = true;
-                    }
-                }
-            }
+    /// Switch models, remembering the current options for the old model and
+    /// restoring any options previously tuned for the new one
+    fn set_model_path(&mut self, path: PathBuf) {
+        if let Some(old_key) = self.model_path.as_deref().and_then(model_key) {
+            self.model_options.insert(old_key, self.options.clone());
         }
-
-        if should_clear_receiver {
-            self.download_receiver = None;
+        if let Some(saved) = model_key(&path).and_then(|k| self.model_options.get(&k)) {
+            // Language follows the audio, not the model
+            let language = self.options.language.take();
+            self.options = saved.clone();
+            self.options.language = language;
         }
+        self.model_path = Some(path);
+    }
 
-        // Auto-select downloaded model
-        if let Some(path) = completed_path {
-            self.model_path = Some(path);
-        }
+    /// Read the models folder's model list again, e.g. after the folder changed
+    fn reload_model_list(&mut self) {
+        (self.models, self.model_list_warning) = load_model_list(&self.models_dir);
+        self.selected_model_idx = self.selected_model_idx.min(self.models.len() - 1);
     }
 
-    fn copy_to_clipboard(&self) {
-        if let Ok(mut clipboard) = arboard::Clipboard::new() {
-            let _ = clipboard.set_text(&self.transcription);
+    /// Re-run the current audio with another of the listed models, downloading it first if needed
+    fn transcribe_with_model(&mut self, idx: usize) {
+        let filename = self.models[idx].filename.clone();
+        // Keep the current settings rather than the ones last used with that model
+        self.model_options.insert(filename.clone(), self.options.clone());
+        let path = self.models_dir.join(filename);
+        if path.exists() {
+            self.set_model_path(path);
+            self.start_transcription();
+        } else {
+            self.selected_model_idx = idx;
+            self.transcribe_after_download = true;
+            self.start_download();
         }
     }
 
-    fn save_to_file(&self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Text", &["txt"])
-            .set_file_name("transcription.txt")
-            .save_file()
-        {
-            let _ = std::fs::write(path, &self.transcription);
+    fn set_audio_path(&mut self, path: PathBuf) {
+        self.audio_tags = read_audio_tags(&path).unwrap_or_default();
+        self.audio_parts.clear();
+        // Keep the decoded samples, waveform and selection when nothing changed
+        let unchanged = self
+            .audio_cache
+            .as_ref()
+            .is_some_and(|cache| cache.is_current(&path, &self.options.resampler));
+        if unchanged {
+            self.audio_reused = true;
+        } else {
+            self.load_waveform(path.clone());
         }
+        self.audio_path = Some(path);
     }
-}
 
-impl eframe::App for WhisperApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.check_messages();
-        self.check_download_messages();
+    /// Select several files to be transcribed as one recording, ordered by name
+    fn set_audio_parts(&mut self, mut paths: Vec<PathBuf>) {
+        paths.sort();
+        self.set_audio_path(paths[0].clone());
+        // The preview only covers a single file
+        self.waveform = None;
+        self.waveform_receiver = None;
+        self.audio_cache = None;
+        self.audio_reused = false;
+        self.audio_parts = paths;
+    }
 
-        // Handle dropped files
-        ctx.input(|i| {
-            for file in &i.raw.dropped_files {
-                if let Some(path) = &file.path {
-                    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                    let ext_lower = ext.to_lowercase();
-                    if ext_lower == "bin" {
-                        self.model_path = Some(path.clone());
-                    } else if AUDIO_EXTENSIONS.contains(&ext_lower.as_str()) {
-                        self.audio_path = Some(path.clone());
-                    }
-                }
-            }
+    /// Decode the audio in the background to draw its waveform
+    fn load_waveform(&mut self, path: PathBuf) {
+        self.waveform = None;
+        self.audio_cache = None;
+        self.audio_reused = false;
+        self.audio_selection = None;
+        self.selection_anchor = None;
+        self.audio_cuts.clear();
+        let resampler = self.options.resampler;
+        let mmap = self.options.mmap_audio;
+
+        let (tx, rx) = channel();
+        self.waveform_receiver = Some(rx);
+
+        thread::spawn(move || {
+            let modified = file_modified(&path);
+            let result = load_audio_to_mono_16khz(&path, &resampler, mmap)
+                .map(|decoded| {
+                    let waveform = Waveform {
+                        duration_secs: decoded.samples.len() as f32 / WHISPER_SAMPLE_RATE as f32,
+                        peaks: waveform_peaks(&decoded.samples, WAVEFORM_BUCKETS),
+                    };
+                    let cache = AudioCache {
+                        path,
+                        modified,
+                        resampler,
+                        audio: Arc::new(decoded),
+                    };
+                    (waveform, cache)
+                })
+                .map_err(|e| e.to_string());
+            tx.send(result).ok();
         });
+    }
 
-        // Request repaint while processing
-        if self.receiver.is_some() || self.download_receiver.is_some() {
-            ctx.request_repaint();
+    fn check_waveform_messages(&mut self) {
+        if let Some(ref receiver) = self.waveform_receiver {
+            if let Ok(result) = receiver.try_recv() {
+                // A file that can't be decoded fails again, with details, when transcribing
+                if let Ok((waveform, cache)) = result {
+                    self.waveform = Some(waveform);
+                    self.audio_cache = Some(cache);
+                }
+                self.waveform_receiver = None;
+            }
         }
+    }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Whisper Transcribe");
-            ui.add_space(10.0);
+    /// Draw the waveform; dragging selects the range to transcribe, clicking clears it
+    fn waveform_ui(&mut self, ui: &mut egui::Ui, lang: UiLanguage) {
+        let Some(ref waveform) = self.waveform else {
+            if self.waveform_receiver.is_some() {
+                ui.spinner();
+            }
+            return;
+        };
+        let duration = waveform.duration_secs.max(0.001);
 
-            // Model download section
-            ui.horizontal(|ui| {
-                ui.label("Download Model:");
-                let selected_name = WHISPER_MODELS[self.selected_model_idx].0;
-                egui::ComboBox::from_id_salt("model_select")
-                    .selected_text(selected_name)
-                    .show_ui(ui, |ui| {
-                        for (idx, (name, _)) in WHISPER_MODELS.iter().enumerate() {
-                            ui.selectable_value(&mut self.selected_model_idx, idx, *name);
-                        }
-                    });
+        let size = egui::vec2(ui.available_width(), 60.0);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click_and_drag());
+        let to_secs = |x: f32| ((x - rect.left()) / rect.width()).clamp(0.0, 1.0) * duration;
+        let to_x = |secs: f32| rect.left() + secs / duration * rect.width();
 
-                let is_downloading = matches!(self.download_status, DownloadStatus::Downloading { .. });
-                if ui.add_enabled(!is_downloading, egui::Button::new("Download")).clicked() {
-                    self.start_download();
-                }
-            });
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+        if let Some((start, end)) = self.audio_selection {
+            let selected = egui::Rect::from_x_y_ranges(to_x(start)..=to_x(end), rect.y_range());
+            painter.rect_filled(selected, 0.0, ui.visuals().selection.bg_fill);
+        }
+        for &(start, end) in &self.audio_cuts {
+            let cut = egui::Rect::from_x_y_ranges(to_x(start)..=to_x(end), rect.y_range());
+            painter.rect_filled(cut, 0.0, egui::Color32::from_rgba_unmultiplied(244, 67, 54, 60));
+        }
+        let stroke = egui::Stroke::new(1.0, ui.visuals().text_color());
+        let bucket_width = rect.width() / waveform.peaks.len().max(1) as f32;
+        for (i, peak) in waveform.peaks.iter().enumerate() {
+            let x = rect.left() + (i as f32 + 0.5) * bucket_width;
+            let half = peak.min(1.0) * rect.height() / 2.0;
+            painter.line_segment(
+                [egui::pos2(x, rect.center().y - half), egui::pos2(x, rect.center().y + half)],
+                stroke,
+            );
+        }
 
-            // Download progress bar
-            match &self.download_status {
-                DownloadStatus::Downloading { downloaded, total } => {
-                    let progress = if *total > 0 {
-                        *downloaded as f32 / *total as f32
-                    } else {
-                        0.0
-                    };
-                    let downloaded_mb = *downloaded as f64 / 1_000_000.0;
-                    let total_mb = *total as f64 / 1_000_000.0;
-                    ui.add(egui::ProgressBar::new(progress).text(format!(
-                        "{:.1} MB / {:.1} MB ({:.0}%)",
-                        downloaded_mb,
-                        total_mb,
-                        progress * 100.0
-                    )));
-                }
-                DownloadStatus::Done => {
-                    ui.colored_label(egui::Color32::from_rgb(76, 175, 80), "Download complete!");
-                }
-                DownloadStatus::Error(e) => {
-                    ui.colored_label(egui::Color32::RED, format!("Download error: {}", e));
-                }
-                DownloadStatus::Idle => {}
+        if response.drag_started() {
+            self.selection_anchor = response.interact_pointer_pos().map(|p| to_secs(p.x));
+        }
+        if response.dragged() {
+            if let (Some(anchor), Some(pos)) = (self.selection_anchor, response.interact_pointer_pos()) {
+                let at = to_secs(pos.x);
+                self.audio_selection = Some((anchor.min(at), anchor.max(at)));
             }
+        }
+        if response.drag_stopped() {
+            self.selection_anchor = None;
+            // Too short to transcribe; treat it as a click
+            if self.audio_selection.is_some_and(|(start, end)| end - start < 0.1) {
+                self.audio_selection = None;
+            }
+        }
+        if response.clicked() {
+            self.audio_selection = None;
+        }
+
+        ui.horizontal(|ui| match self.audio_selection {
+            Some((start, end)) => {
+                ui.label(format!(
+                    "{} {} – {}",
+                    tr(lang, "Selection:"),
+                    format_clock(start),
+                    format_clock(end)
+                ));
+                if ui.small_button(tr(lang, "Clear selection")).clicked() {
+                    self.audio_selection = None;
+                }
+                if ui
+                    .small_button(tr(lang, "Cut out"))
+                    .on_hover_text(tr(lang, "Leave this range out of the transcription, e.g. an ad break"))
+                    .clicked()
+                {
+                    self.audio_cuts.push((start, end));
+                    self.audio_selection = None;
+                }
+            }
+            None => {
+                ui.weak(tr(lang, "Drag across the waveform to transcribe only part of it"));
+            }
+        });
+        if !self.audio_cuts.is_empty() {
+            ui.horizontal(|ui| {
+                let removed: f32 = self.audio_cuts.iter().map(|(start, end)| end - start).sum();
+                ui.label(format!(
+                    "{} {} ({:.1} s)",
+                    self.audio_cuts.len(),
+                    tr(lang, "cut out"),
+                    removed
+                ));
+                if ui.small_button(tr(lang, "Restore cuts")).clicked() {
+                    self.audio_cuts.clear();
+                }
+                ui.checkbox(&mut self.options.cut_times_original, tr(lang, "Times on the original audio"))
+                    .on_hover_text(tr(
+                        lang,
+                        "Off: times follow the audio with the cuts taken out. On: times match the source file.",
+                    ));
+            });
+        }
+    }
+
+    /// Start transcribing, first asking for confirmation when an English-only model
+    /// is about to be given another language
+    fn start_transcription(&mut self) {
+        let english_only = self
+            .model_path
+            .as_deref()
+            .and_then(model_is_multilingual)
+            .is_some_and(|multilingual| !multilingual);
+        match self.options.language.as_deref() {
+            Some(code) if english_only && code != "en" => {
+                self.language_mismatch = Some(language_name(code).unwrap_or_else(|| code.to_string()));
+            }
+            _ => self.launch_transcription(),
+        }
+    }
+
+    /// Transcribe the audio, then keep the text of an existing SRT and give its cues the new timing
+    fn retime_subtitles(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("SubRip", &["srt"])
+            .pick_file()
+        else {
+            return;
+        };
+        let cues = match std::fs::read_to_string(&path) {
+            Ok(contents) => align::parse_srt(&contents),
+            Err(e) => {
+                self.transcribe_warning = Some(format!("Couldn't read {}: {}", path.display(), e));
+                return;
+            }
+        };
+        if cues.is_empty() {
+            self.transcribe_warning = Some(format!("No subtitle cues found in {}", path.display()));
+            return;
+        }
+        self.retime_cues = Some(cues);
+        self.start_transcription();
+    }
+
+    fn launch_transcription(&mut self) {
+        let model_path = self.model_path.clone().unwrap();
+        let audio_path = self.audio_path.clone().unwrap();
+        let audio = self.audio_input(audio_path);
+        let mut options = self.options.clone();
+        options.selection = self.audio_selection;
+        options.cuts = self.audio_cuts.clone();
+        self.run_cuts = if options.cut_times_original {
+            Vec::new()
+        } else {
+            options.cuts.clone()
+        };
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.transcribe_cancel = cancel.clone();
+
+        let (tx, rx) = channel();
+        self.receiver = Some(rx);
+        self.splice_receiver = None;
+        self.status = TranscribeStatus::Loading;
+        self.transcribe_warning = None;
+        self.last_stats = None;
+        self.source_deleted = false;
+        self.stop_playback();
+        self.transcribed_sources = if self.audio_parts.len() > 1 {
+            self.audio_parts.clone()
+        } else {
+            self.audio_path.iter().cloned().collect()
+        };
+        self.progress = None;
+        self.disagreements = None;
+        self.detected_language = None;
+        self.transcription.clear();
+        self.segments.clear();
+        self.original_text.clear();
+        self.checked_segments.clear();
+
+        let ensemble = self.ensemble_model.as_ref().map(|name| self.models_dir.join(name));
+        let ensemble = match ensemble {
+            Some(path) if !path.is_file() => {
+                self.transcribe_warning =
+                    Some(format!("{} isn't in the models folder, so only one model is used", path.display()));
+                None
+            }
+            other => other.filter(|path| *path != model_path),
+        };
+
+        thread::spawn(move || {
+            if let Some(second) = ensemble {
+                run_ensemble_transcription(vec![model_path, second], audio, options, cancel, tx);
+            } else if options.verify_on_cpu && options.use_gpu {
+                run_verified_transcription(model_path, audio, options, cancel, tx);
+            } else {
+                run_transcription(model_path, audio, options, cancel, tx);
+            }
+        });
+    }
+
+    /// The loaded audio for a run, reusing the decoded samples when they're still current
+    fn audio_input(&mut self, audio_path: PathBuf) -> AudioInput {
+        if self.audio_parts.len() > 1 {
+            AudioInput::Joined(self.audio_parts.clone())
+        } else {
+            match self.audio_cache {
+                Some(ref cache) if cache.is_current(&audio_path, &self.options.resampler) => {
+                    AudioInput::Decoded(cache.audio.clone())
+                }
+                Some(ref cache) if cache.path == audio_path => {
+                    // Edited on disk (or new resampler settings) since it was decoded; refresh the preview too
+                    self.load_waveform(audio_path.clone());
+                    AudioInput::File(audio_path)
+                }
+                _ => AudioInput::File(audio_path),
+            }
+        }
+    }
+
+    /// Transcribe one segment's span again with the current settings, e.g. another language
+    /// or beam size, to replace a garbled line without re-running the whole file
+    fn retranscribe_segment(&mut self, idx: usize) {
+        let (Some(model_path), Some(audio_path), Some(segment)) =
+            (self.model_path.clone(), self.audio_path.clone(), self.segments.get(idx))
+        else {
+            return;
+        };
+        // The segment's times may leave out the run's cuts; pick its span on the source and cut again
+        let mut options = self.options.clone();
+        let source = |cs: i64| uncut_timestamp(cs, &self.run_cuts) as f32 / 100.0;
+        options.selection = Some((source(segment.start), source(segment.end)));
+        options.cuts = self.run_cuts.clone();
+        options.cut_times_original = false;
+        let audio = self.audio_input(audio_path);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.transcribe_cancel = cancel.clone();
+
+        let (tx, rx) = channel();
+        self.splice_receiver = Some(rx);
+        self.splice_segment = idx;
+        self.transcribe_warning = None;
+        self.stop_playback();
+
+        thread::spawn(move || {
+            run_transcription(model_path, audio, options, cancel, tx);
+        });
+    }
+
+    fn check_splice_messages(&mut self) {
+        let mut should_clear_receiver = false;
+        let mut replacement = None;
+
+        if let Some(ref receiver) = self.splice_receiver {
+            while let Ok(msg) = receiver.try_recv() {
+                match msg {
+                    TranscribeMessage::Warning(w) => self.transcribe_warning = Some(w),
+                    TranscribeMessage::Done(segments, _) => {
+                        replacement = Some(segments);
+                        should_clear_receiver = true;
+                    }
+                    TranscribeMessage::Cancelled => should_clear_receiver = true,
+                    TranscribeMessage::Error(e) => {
+                        self.transcribe_warning =
+                            Some(format!("Re-transcribing the segment failed: {}", e));
+                        should_clear_receiver = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if should_clear_receiver {
+            self.splice_receiver = None;
+        }
+        let Some(segments) = replacement else {
+            return;
+        };
+        let idx = self.splice_segment;
+        if idx >= self.segments.len() {
+            return;
+        }
+        if segments.iter().all(|s| s.text.trim().is_empty()) {
+            self.transcribe_warning =
+                Some("No speech found in that segment; its text was kept".to_string());
+            return;
+        }
+        let texts: Vec<String> = segments.iter().map(|s| s.text.clone()).collect();
+        self.segments.splice(idx..=idx, segments);
+        if idx < self.original_text.len() {
+            self.original_text.splice(idx..=idx, texts);
+        }
+        self.refresh_transcription();
+    }
+
+    fn check_messages(&mut self) {
+        let mut should_clear_receiver = false;
+        let mut segments_changed = false;
+        let mut finished = None;
+
+        if let Some(ref receiver) = self.receiver {
+            while let Ok(msg) = receiver.try_recv() {
+                match msg {
+                    TranscribeMessage::Status(s) => {
+                        if s.contains("Transcribing") {
+                            self.status = TranscribeStatus::Transcribing;
+                        } else if s.contains("Verifying") {
+                            self.status = TranscribeStatus::Verifying;
+                        }
+                    }
+                    TranscribeMessage::GpuStatus(gpu, device) => {
+                        self.using_gpu = Some(gpu);
+                        self.gpu_device = device;
+                    }
+                    TranscribeMessage::DetectedLanguage(lang) => {
+                        self.detected_language = Some(lang);
+                    }
+                    TranscribeMessage::Warning(w) => {
+                        self.transcribe_warning = Some(w);
+                    }
+                    TranscribeMessage::Segment(segment) => {
+                        self.segments.push(segment);
+                        segments_changed = true;
+                    }
+                    TranscribeMessage::Progress(percent) => {
+                        self.progress = Some(percent);
+                    }
+                    TranscribeMessage::ClearSegments => {
+                        self.progress = None;
+                        self.segments.clear();
+                        self.transcription.clear();
+                    }
+                    TranscribeMessage::Disagreements(d) => {
+                        self.disagreements = Some(d);
+                    }
+                    TranscribeMessage::Done(segments, stats) => {
+                        finished = Some(stats);
+                        self.segments = match self.retime_cues.take() {
+                            Some(cues) => align::retime_cues(&cues, &segments),
+                            None => segments,
+                        };
+                        segments_changed = true;
+                        self.last_stats = Some(stats);
+                        self.status = TranscribeStatus::Done;
+                        should_clear_receiver = true;
+                        if self.notify_on_done {
+                            notify_transcription_done(self.audio_path.as_ref());
+                        }
+                    }
+                    TranscribeMessage::Cancelled => {
+                        // Keep whatever segments were streamed before the cancel
+                        self.status = TranscribeStatus::Cancelled;
+                        self.retime_cues = None;
+                        should_clear_receiver = true;
+                    }
+                    TranscribeMessage::Error(e) => {
+                        self.status = TranscribeStatus::Error(e);
+                        self.retime_cues = None;
+                        should_clear_receiver = true;
+                    }
+                }
+            }
+        }
+
+        if segments_changed {
+            self.refresh_transcription();
+        }
+        if should_clear_receiver {
+            self.receiver = None;
+            self.original_text = self.segments.iter().map(|s| s.text.clone()).collect();
+        }
+        if let Some(stats) = finished {
+            self.record_speed(&stats);
+        }
+    }
+
+    fn start_download(&mut self) {
+        let model = self.models[self.selected_model_idx].clone();
+        let dest_path = self.models_dir.join(&model.filename);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.download_cancel = cancel.clone();
+        let network = self.network.clone();
+        let temp_dir = self.temp_dir.clone();
+
+        let (tx, rx) = channel();
+        self.download_receiver = Some(rx);
+        self.download_status = DownloadStatus::Downloading { downloaded: 0, total: 0 };
+        self.download_started = Some(Instant::now());
+        self.download_source = None;
+
+        thread::spawn(move || {
+            run_download(model, dest_path, network, temp_dir, cancel, tx);
+        });
+    }
+
+    fn check_download_messages(&mut self) {
+        let mut should_clear_receiver = false;
+        let mut completed_path: Option<PathBuf> = None;
+
+        if let Some(ref receiver) = self.download_receiver {
+            while let Ok(msg) = receiver.try_recv() {
+                match msg {
+                    DownloadMessage::Source { host, url } => {
+                        self.download_source = Some((host, url));
+                    }
+                    DownloadMessage::Progress { downloaded, total } => {
+                        self.download_status = DownloadStatus::Downloading { downloaded, total };
+                    }
+                    DownloadMessage::Verifying => {
+                        self.download_status = DownloadStatus::Verifying;
+                    }
+                    DownloadMessage::Done(path) => {
+                        self.download_status = DownloadStatus::Done;
+                        completed_path = Some(path);
+                        should_clear_receiver = true;
+                    }
+                    DownloadMessage::Error(e) => {
+                        self.download_status = DownloadStatus::Error(e);
+                        self.transcribe_after_download = false;
+                        should_clear_receiver = true;
+                    }
+                    DownloadMessage::Invalid(e) => {
+                        self.download_status = DownloadStatus::Invalid(e);
+                        self.transcribe_after_download = false;
+                        should_clear_receiver = true;
+                    }
+                }
+            }
+        }
+
+        if should_clear_receiver {
+            self.download_receiver = None;
+        }
+
+        // Auto-select downloaded model
+        if let Some(path) = completed_path {
+            self.set_model_path(path);
+
+            if self.transcribe_after_download {
+                self.transcribe_after_download = false;
+                if self.audio_path.is_some() && self.receiver.is_none() {
+                    self.start_transcription();
+                }
+            }
+        }
+    }
+
+    /// Time every local model on the built-in benchmark clip
+    fn start_benchmark(&mut self) {
+        let models = local_models(&self.models_dir);
+        let options = self.options.clone();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.benchmark_cancel = cancel.clone();
+
+        let (tx, rx) = channel();
+        self.benchmark_receiver = Some(rx);
+        self.benchmark_results.clear();
+
+        thread::spawn(move || {
+            run_benchmark(models, options, cancel, tx);
+        });
+    }
+
+    fn check_benchmark_messages(&mut self) {
+        let mut should_clear_receiver = false;
+
+        if let Some(ref receiver) = self.benchmark_receiver {
+            while let Ok(msg) = receiver.try_recv() {
+                match msg {
+                    BenchmarkMessage::Running(model) => self.benchmark_running = Some(model),
+                    BenchmarkMessage::Result(result) => self.benchmark_results.push(result),
+                    BenchmarkMessage::Done => should_clear_receiver = true,
+                }
+            }
+        }
+
+        if should_clear_receiver {
+            self.benchmark_receiver = None;
+            self.benchmark_running = None;
+        }
+    }
+
+    /// Check the decode and resample pipeline and the selected model without the user's audio
+    fn start_self_test(&mut self) {
+        let model_path = self.model_path.clone();
+        let resampler = self.options.resampler;
+        let (tx, rx) = channel();
+        self.self_test_receiver = Some(rx);
+        self.self_test_results.clear();
+        thread::spawn(move || {
+            tx.send(run_self_test(model_path.as_deref(), &resampler)).ok();
+        });
+    }
+
+    fn check_self_test(&mut self) {
+        if let Some(results) = self.self_test_receiver.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.self_test_results = results;
+            self.self_test_receiver = None;
+        }
+    }
+
+    /// Queue more files for batch transcription, skipping ones already queued
+    fn add_batch_files(&mut self, paths: Vec<PathBuf>) {
+        for path in paths {
+            if !self.batch.iter().any(|item| item.path == path) {
+                self.batch.push(BatchItem::new(path));
+            }
+        }
+    }
+
+    /// Keep only a sample of the waiting files, for spot-checking a large corpus
+    fn sample_batch(&mut self) {
+        let pending: Vec<usize> = (0..self.batch.len())
+            .filter(|&idx| matches!(self.batch[idx].status, BatchStatus::Pending))
+            .collect();
+        let seed = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        let mut keep = vec![true; self.batch.len()];
+        for &idx in &pending {
+            keep[idx] = false;
+        }
+        let picked = self.batch_sample.pick(pending.len(), seed);
+        for &i in &picked {
+            keep[pending[i]] = true;
+        }
+        let mut keep = keep.into_iter();
+        self.batch.retain(|_| keep.next().unwrap_or(true));
+        let lang = self.ui_language;
+        self.batch_note = Some(format!(
+            "{} {} / {} ({}); {}",
+            tr(lang, "Waiting files kept:"),
+            picked.len(),
+            pending.len(),
+            self.batch_sample.describe(lang),
+            tr(lang, "the queue lists the ones picked")
+        ));
+    }
+
+    /// Queue files opened with the app from the file manager
+    fn queue_incoming_files(&mut self, paths: Vec<PathBuf>) {
+        if paths.is_empty() {
+            return;
+        }
+        self.batch_note = Some(format!("{} {}", tr(self.ui_language, "Files added to the queue:"), paths.len()));
+        self.add_batch_files(paths);
+    }
+
+    fn check_incoming_files(&mut self, ctx: &egui::Context) {
+        let Some(ref receiver) = self.incoming_files else {
+            return;
+        };
+        let paths: Vec<PathBuf> = receiver.try_iter().collect();
+        if !paths.is_empty() {
+            self.queue_incoming_files(paths);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+    }
+
+    /// Pick the output folder, then measure the queue so the user can confirm before it starts
+    fn start_batch(&mut self) {
+        let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+        self.batch_output_dir = Some(dir);
+        let paths: Vec<PathBuf> = self
+            .batch
+            .iter()
+            .filter(|item| matches!(item.status, BatchStatus::Pending))
+            .map(|item| item.path.clone())
+            .collect();
+        let (tx, rx) = channel();
+        self.batch_estimate_receiver = Some(rx);
+        thread::spawn(move || {
+            tx.send(BatchEstimate::probe(&paths)).ok();
+        });
+    }
+
+    fn run_batch(&mut self) {
+        self.batch_note = None;
+        self.batch_deleted = 0;
+        self.batch_cancel = Arc::new(AtomicBool::new(false));
+        self.start_next_batch_item();
+    }
+
+    /// Remember how fast the current model ran, for batch time estimates
+    fn record_speed(&mut self, stats: &TranscribeStats) {
+        // Model loading dominates short clips
+        if stats.audio_secs < 10.0 {
+            return;
+        }
+        if let Some(key) = self.model_path.as_deref().and_then(model_key) {
+            self.model_speed
+                .insert(key, stats.audio_secs / stats.elapsed_secs.max(0.001));
+        }
+    }
+
+    /// Summary of the batch about to start, with a time estimate when the model's speed is known
+    fn confirm_batch_ui(&mut self, ctx: &egui::Context) {
+        let lang = self.ui_language;
+        if let Some(estimate) = self.batch_estimate_receiver.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.batch_estimate = Some(estimate);
+            self.batch_estimate_receiver = None;
+        }
+
+        let modal = egui::Modal::new(egui::Id::new("confirm_batch")).show(ctx, |ui| {
+            ui.set_width(360.0);
+            ui.heading(tr(lang, "Start batch?"));
+            ui.add_space(5.0);
+            let Some(ref estimate) = self.batch_estimate else {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr(lang, "Checking the files..."));
+                });
+                return;
+            };
+
+            ui.label(format!(
+                "{}: {}, {} {}",
+                tr(lang, "Files"),
+                estimate.files,
+                format_duration(estimate.audio_secs),
+                tr(lang, "of audio")
+            ));
+            if estimate.unknown > 0 {
+                ui.weak(format!(
+                    "{} {}",
+                    estimate.unknown,
+                    tr(lang, "files don't record their length and aren't counted")
+                ));
+            }
+            let model = self.model_path.as_deref().and_then(model_key);
+            match model.as_ref().and_then(|key| self.model_speed.get(key)) {
+                Some(speed) => {
+                    ui.label(format!(
+                        "{} {}",
+                        tr(lang, "Estimated time:"),
+                        format_duration(estimate.audio_secs / *speed as f64)
+                    ));
+                }
+                None => {
+                    ui.weak(tr(lang, "No speed measured for this model yet, so there's no time estimate"));
+                }
+            }
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button(tr(lang, "Start")).clicked() {
+                    self.batch_estimate = None;
+                    self.run_batch();
+                }
+                if ui.button(tr(lang, "Cancel")).clicked() {
+                    self.batch_estimate = None;
+                }
+            });
+        });
+        if modal.should_close() {
+            self.batch_estimate = None;
+            self.batch_estimate_receiver = None;
+        }
+    }
+
+    /// Start the first waiting file, or wrap up once none are left
+    fn start_next_batch_item(&mut self) {
+        let Some(model_path) = self.model_path.clone() else {
+            return;
+        };
+        let Some(item) = self
+            .batch
+            .iter_mut()
+            .find(|item| matches!(item.status, BatchStatus::Pending))
+        else {
+            self.batch_receiver = None;
+            self.finish_batch();
+            return;
+        };
+        item.status = BatchStatus::Running;
+        item.detected_language = None;
+        let audio = AudioInput::File(item.path.clone());
+        let options = self.options.clone();
+        let cancel = self.batch_cancel.clone();
+        self.save_batch_progress();
+
+        let (tx, rx) = channel();
+        self.batch_receiver = Some(rx);
+        thread::spawn(move || {
+            run_transcription(model_path, audio, options, cancel, tx);
+        });
+    }
+
+    fn check_batch_messages(&mut self) {
+        let Some(ref receiver) = self.batch_receiver else {
+            return;
+        };
+        let messages: Vec<TranscribeMessage> = receiver.try_iter().collect();
+        let Some(idx) = self
+            .batch
+            .iter()
+            .position(|item| matches!(item.status, BatchStatus::Running))
+        else {
+            return;
+        };
+
+        let mut next = false;
+        for msg in messages {
+            match msg {
+                TranscribeMessage::GpuStatus(gpu, device) => {
+                    self.using_gpu = Some(gpu);
+                    self.gpu_device = device;
+                }
+                TranscribeMessage::DetectedLanguage(lang) => {
+                    self.batch[idx].detected_language = Some(lang);
+                }
+                TranscribeMessage::Done(segments, stats) => {
+                    self.record_speed(&stats);
+                    let saved = self.save_batch_item(idx, &segments);
+                    // Only a transcript written next to the others lets the audio go right away
+                    if saved.is_ok() && self.batch_individual_files && self.batch_output_dir.is_some() {
+                        self.delete_batch_source(idx);
+                    }
+                    self.batch[idx].status = match saved {
+                        Ok(()) => BatchStatus::Done(segments),
+                        Err(e) => BatchStatus::Failed(e),
+                    };
+                    next = true;
+                }
+                TranscribeMessage::Error(e) => {
+                    self.batch[idx].status = BatchStatus::Failed(e);
+                    next = true;
+                }
+                TranscribeMessage::Cancelled => {
+                    self.batch[idx].status = BatchStatus::Cancelled;
+                    self.batch_receiver = None;
+                    Self::clear_batch_progress();
+                    self.batch_note = Some(tr(self.ui_language, "Batch stopped").to_string());
+                }
+                _ => {}
+            }
+        }
+
+        if next {
+            self.start_next_batch_item();
+        }
+    }
+
+    /// Save a finished file's transcript into the batch folder, if individual files are wanted
+    fn save_batch_item(&self, idx: usize, segments: &[Segment]) -> Result<(), String> {
+        if !self.batch_individual_files {
+            return Ok(());
+        }
+        let Some(ref dir) = self.batch_output_dir else {
+            return Ok(());
+        };
+        let item = &self.batch[idx];
+        let detected = item.detected_language.as_deref();
+        let name = self
+            .output_file_name_for(Some(&item.path), detected, &self.batch_format)
+            .map_err(|e| e.to_string())?;
+        let path = dir.join(name);
+        std::fs::write(&path, self.export_segments(segments, detected, &self.batch_format))
+            .map_err(|e| format!("Couldn't save {}: {}", path.display(), e))?;
+        self.run_post_command(&path);
+        Ok(())
+    }
+
+    /// Record which files are done so far, for resuming after a crash
+    fn save_batch_progress(&mut self) {
+        let (Some(path), Some(dir)) = (batch_progress_path(), self.batch_output_dir.as_deref()) else {
+            return;
+        };
+        if let Err(e) = BatchProgress::new(&self.batch, dir).save(&path) {
+            self.batch_note = Some(format!(
+                "{:#}; {}",
+                e,
+                tr(self.ui_language, "the batch can't be resumed after a crash")
+            ));
+        }
+    }
+
+    fn clear_batch_progress() {
+        if let Some(path) = batch_progress_path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Carry on with a batch from a previous session, skipping the files it finished
+    fn resume_batch(&mut self, progress: BatchProgress) {
+        self.batch_output_dir = Some(progress.output_dir.clone());
+        self.batch = progress.into_queue();
+        if self.model_path.is_some() {
+            self.run_batch();
+        } else {
+            self.batch_note = Some(tr(self.ui_language, "Select a model, then start the batch again").to_string());
+        }
+    }
+
+    /// Offer to pick up a batch the last session didn't finish
+    fn resume_batch_ui(&mut self, ctx: &egui::Context) {
+        let lang = self.ui_language;
+        let Some(ref progress) = self.unfinished_batch else {
+            return;
+        };
+        let mut resume = false;
+        let mut discard = false;
+        let modal = egui::Modal::new(egui::Id::new("resume_batch")).show(ctx, |ui| {
+            ui.set_width(360.0);
+            ui.heading(tr(lang, "Resume unfinished batch?"));
+            ui.add_space(5.0);
+            ui.label(format!(
+                "{} {} / {}",
+                tr(lang, "Files left to transcribe:"),
+                progress.remaining(),
+                progress.items.len()
+            ));
+            ui.label(format!("{} {}", tr(lang, "Output folder:"), progress.output_dir.display()));
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button(tr(lang, "Resume")).clicked() {
+                    resume = true;
+                }
+                if ui.button(tr(lang, "Discard")).clicked() {
+                    discard = true;
+                }
+            });
+        });
+        if resume {
+            if let Some(progress) = self.unfinished_batch.take() {
+                self.resume_batch(progress);
+            }
+        } else if discard {
+            self.unfinished_batch = None;
+            Self::clear_batch_progress();
+        } else if modal.should_close() {
+            // Dismissed without deciding; ask again next launch
+            self.unfinished_batch = None;
+        }
+    }
+
+    /// Summarize the finished batch and write the combined document if requested
+    fn finish_batch(&mut self) {
+        let lang = self.ui_language;
+        Self::clear_batch_progress();
+        let done = self
+            .batch
+            .iter()
+            .filter(|item| matches!(item.status, BatchStatus::Done(_)))
+            .count();
+        let failed = self
+            .batch
+            .iter()
+            .filter(|item| matches!(item.status, BatchStatus::Failed(_)))
+            .count();
+        let mut note = format!(
+            "{} {} {}, {} {}",
+            tr(lang, "Batch finished:"),
+            done,
+            tr(lang, "done"),
+            failed,
+            tr(lang, "failed")
+        );
+
+        // Failed files with their reasons, for triage
+        if let (Some(dir), Some(report)) = (&self.batch_output_dir, failure_report(&self.batch)) {
+            let path = dir.join("failed_files.csv");
+            match std::fs::write(&path, report) {
+                Ok(()) => note.push_str(&format!(" ({} {})", tr(lang, "listed in"), path.display())),
+                Err(e) => note.push_str(&format!(" ({} {})", tr(lang, "couldn't save the failure list:"), e)),
+            }
+        }
+
+        if let (Some(dir), true) = (&self.batch_output_dir, self.batch_combined) {
+            let entries: Vec<(String, Result<String, String>)> = self
+                .batch
+                .iter()
+                .filter_map(|item| match item.status {
+                    BatchStatus::Done(ref segments) => Some((
+                        item.name(),
+                        Ok(segments_to_text(
+                            &self.prepare_segments(segments, item.detected_language.as_deref()),
+                        )),
+                    )),
+                    BatchStatus::Failed(ref e) => Some((item.name(), Err(e.clone()))),
+                    _ => None,
+                })
+                .collect();
+            let format = self.batch_combined_format;
+            let path = dir.join(format!("combined_transcript.{}", format.extension()));
+            match std::fs::write(&path, self.line_ending.apply(&combined_document(&entries, format))) {
+                Ok(()) => {
+                    note.push_str(&format!("; {} {}", tr(lang, "combined transcript saved to"), path.display()));
+                    self.run_post_command(&path);
+                    if !self.batch_individual_files {
+                        for idx in 0..self.batch.len() {
+                            if matches!(self.batch[idx].status, BatchStatus::Done(_)) {
+                                self.delete_batch_source(idx);
+                            }
+                        }
+                    }
+                }
+                Err(e) => note.push_str(&format!("; {} {}", tr(lang, "couldn't save the combined transcript:"), e)),
+            }
+        }
+        if self.batch_deleted > 0 {
+            note.push_str(&format!("; {} {}", tr(lang, "source files deleted:"), self.batch_deleted));
+        }
+        self.batch_note = Some(note);
+    }
+
+    /// Delete a batch file's audio after its transcript was saved, if the user opted in
+    fn delete_batch_source(&mut self, idx: usize) {
+        if self.delete_audio_after_save
+            && self.delete_audio_confirmed
+            && std::fs::remove_file(&self.batch[idx].path).is_ok()
+        {
+            self.batch_deleted += 1;
+        }
+    }
+
+    /// Delete the audio the finished transcript came from, if the user opted in.
+    /// Only a completed run counts; errors and cancelled runs keep their audio.
+    fn delete_transcribed_audio(&mut self) {
+        if !self.delete_audio_after_save
+            || !self.delete_audio_confirmed
+            || self.status != TranscribeStatus::Done
+        {
+            return;
+        }
+        for path in std::mem::take(&mut self.transcribed_sources) {
+            if let Err(e) = std::fs::remove_file(&path) {
+                self.transcribe_warning = Some(format!("Couldn't delete {}: {}", path.display(), e));
+                return;
+            }
+            self.source_deleted = true;
+        }
+    }
+
+    /// Cancel any in-flight download and transcription and return to a clean idle state
+    fn abort_all(&mut self) {
+        self.transcribe_cancel.store(true, Ordering::Relaxed);
+        self.download_cancel.store(true, Ordering::Relaxed);
+        self.benchmark_cancel.store(true, Ordering::Relaxed);
+        self.batch_cancel.store(true, Ordering::Relaxed);
+        Self::clear_batch_progress();
+        for item in &mut self.batch {
+            if matches!(item.status, BatchStatus::Running) {
+                item.status = BatchStatus::Cancelled;
+            }
+        }
+        self.receiver = None;
+        self.splice_receiver = None;
+        self.download_receiver = None;
+        self.benchmark_receiver = None;
+        self.batch_receiver = None;
+        self.benchmark_running = None;
+        self.status = TranscribeStatus::Idle;
+        self.download_status = DownloadStatus::Idle;
+        self.transcribe_after_download = false;
+    }
+
+    /// Short guided setup on first launch: model, models folder and GPU preference
+    fn setup_wizard(&mut self, ctx: &egui::Context) {
+        let lang = self.ui_language;
+        let modal = egui::Modal::new(egui::Id::new("setup_wizard")).show(ctx, |ui| {
+            ui.set_width(380.0);
+            ui.heading(tr(lang, "Welcome to Whisper Transcribe"));
+            ui.add_space(5.0);
+            ui.label(tr(lang, "Pick a model to download. Larger models are more accurate but slower."));
+            ui.add_space(5.0);
+
+            let recommended = recommended_model_idx(&self.models, self.gpu_available);
+            ui.horizontal(|ui| {
+                ui.label(tr(lang, "Model:"));
+                egui::ComboBox::from_id_salt("wizard_model_select")
+                    .selected_text(&self.models[self.selected_model_idx].name)
+                    .show_ui(ui, |ui| {
+                        for (idx, model) in self.models.iter().enumerate() {
+                            let label = model_label(model, Some(lang));
+                            ui.selectable_value(&mut self.selected_model_idx, idx, label);
+                        }
+                    });
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} {}",
+                        tr(lang, "Recommended for this machine:"),
+                        self.models[recommended].name
+                    ))
+                    .weak(),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr(lang, "Models folder:"));
+                ui.label(self.models_dir.display().to_string());
+                if ui.button(tr(lang, "Change...")).clicked() {
+                    if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                        self.models_dir = dir;
+                        self.reload_model_list();
+                    }
+                }
+            });
+
+            ui.add_enabled(
+                self.gpu_available,
+                egui::Checkbox::new(
+                    &mut self.options.use_gpu,
+                    format!("{} ({})", tr(lang, "Use GPU when available"), GPU_BACKEND.unwrap_or("none")),
+                ),
+            );
+            if !self.gpu_available {
+                ui.label(egui::RichText::new(tr(lang, "No usable GPU found; CPU will be used.")).weak());
+            }
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button(tr(lang, "Download and start")).clicked() {
+                    self.start_download();
+                    self.show_setup_wizard = false;
+                }
+                if ui.button(tr(lang, "Skip")).clicked() {
+                    self.show_setup_wizard = false;
+                }
+            });
+        });
+
+        if modal.should_close() {
+            self.show_setup_wizard = false;
+        }
+    }
+
+    /// Warn that an English-only model can't transcribe the selected language
+    fn language_mismatch_ui(&mut self, ctx: &egui::Context) {
+        let lang = self.ui_language;
+        let Some(language) = self.language_mismatch.clone() else {
+            return;
+        };
+        let modal = egui::Modal::new(egui::Id::new("language_mismatch")).show(ctx, |ui| {
+            ui.set_width(360.0);
+            ui.heading(tr(lang, "English-only model"));
+            ui.add_space(5.0);
+            ui.label(tr(lang, "The selected model only understands English."));
+            ui.label(format!("{} {}", tr(lang, "Selected language:"), language));
+            ui.label(tr(lang, "Pick a multilingual model (without \".en\" in its name) for other languages."));
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button(tr(lang, "Transcribe anyway")).clicked() {
+                    self.language_mismatch = None;
+                    self.launch_transcription();
+                }
+                if ui.button(tr(lang, "Cancel")).clicked() {
+                    self.language_mismatch = None;
+                }
+            });
+        });
+        if modal.should_close() {
+            self.language_mismatch = None;
+        }
+    }
+
+    /// Ask once before turning on deletion of transcribed audio
+    fn confirm_delete_audio_ui(&mut self, ctx: &egui::Context) {
+        let lang = self.ui_language;
+        let modal = egui::Modal::new(egui::Id::new("confirm_delete_audio")).show(ctx, |ui| {
+            ui.set_width(360.0);
+            ui.heading(tr(lang, "Delete audio after transcription?"));
+            ui.add_space(5.0);
+            ui.label(tr(
+                lang,
+                "Each audio file will be permanently deleted once its transcript has been saved. \
+                 Files are never deleted when transcription fails or is cancelled.",
+            ));
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button(tr(lang, "Delete after saving")).clicked() {
+                    self.delete_audio_after_save = true;
+                    self.delete_audio_confirmed = true;
+                    self.confirm_delete_audio = false;
+                }
+                if ui.button(tr(lang, "Cancel")).clicked() {
+                    self.confirm_delete_audio = false;
+                }
+            });
+        });
+        if modal.should_close() {
+            self.confirm_delete_audio = false;
+        }
+    }
+
+    /// Select an audio file copied to the clipboard (a copied file, or its path as text)
+    fn paste_audio_from_clipboard(&mut self) {
+        let Ok(mut clipboard) = arboard::Clipboard::new() else {
+            return;
+        };
+        let from_files = clipboard
+            .get()
+            .file_list()
+            .ok()
+            .and_then(|files| files.into_iter().find(|p| is_audio_file(p)));
+        let path = from_files.or_else(|| {
+            clipboard
+                .get_text()
+                .ok()
+                .and_then(|text| audio_path_from_text(&text))
+        });
+        // Ctrl+V can arrive both as pasted text and as the key, so don't load the same file twice
+        if let Some(path) = path.filter(|p| self.audio_path.as_ref() != Some(p)) {
+            self.set_audio_path(path);
+        }
+    }
+
+    fn copy_to_clipboard(&mut self) {
+        let text = self.transcription.clone();
+        self.copy_text(&text);
+    }
+
+    /// Copy the transcript under a line naming the source file and its length, for sharing in chats
+    fn copy_with_source_header(&mut self) {
+        let mut header = match self.audio_path {
+            Some(ref path) => path
+                .file_name()
+                .map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned()),
+            None => "Transcript".to_string(),
+        };
+        if self.audio_parts.len() > 1 {
+            header.push_str(&format!(" (+{} more)", self.audio_parts.len() - 1));
+        }
+        let duration = self
+            .waveform
+            .as_ref()
+            .map(|w| w.duration_secs as f64)
+            .or(self.last_stats.map(|s| s.audio_secs as f64));
+        if let Some(secs) = duration {
+            header.push_str(&format!(" — {}", format_duration(secs)));
+        }
+        let text = format!("{}\n\n{}", header, self.transcription);
+        self.copy_text(&text);
+    }
+
+    fn copy_text(&mut self, text: &str) {
+        let result = arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(text))
+            .map_err(|e| e.to_string());
+        self.copy_feedback = Some((result, Instant::now()));
+    }
+
+    /// Suggested export name, based on the audio's title tag when it has one
+    fn default_file_name(&self, ext: &str) -> String {
+        let stem = self
+            .audio_tags
+            .title
+            .as_deref()
+            .map(sanitize_file_name)
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "transcription".to_string());
+        format!("{}.{}", stem, ext)
+    }
+
+    /// File contents for an export format, chosen by extension (plain text by default)
+    fn export_contents(&self, ext: &str) -> String {
+        self.export_segments(&self.segments, self.detected_language.as_deref(), ext)
+    }
+
+    /// Export any run's segments with the current output settings
+    fn export_segments(&self, segments: &[Segment], detected_language: Option<&str>, ext: &str) -> String {
+        self.format_segments(self.prepare_segments(segments, detected_language), ext)
+    }
+
+    /// Only the segments ticked in the list view, in an export format
+    fn export_checked(&self, ext: &str) -> String {
+        let checked = self
+            .output_segments()
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| self.checked_segments.contains(idx))
+            .map(|(_, segment)| segment)
+            .collect();
+        self.format_segments(checked, ext)
+    }
+
+    /// Segments that already went through prepare_segments, written out in an export format
+    fn format_segments(&self, segments: Vec<Segment>, ext: &str) -> String {
+        let contents = match ext {
+            "srt" => segments_to_srt(&self.subtitle_segments(segments)),
+            "vtt" => segments_to_vtt(&self.subtitle_segments(segments)),
+            "json" => segments_to_json(&segments, self.json_time_unit),
+            "jsonl" => segments_to_jsonl(&segments, self.json_time_unit),
+            _ => segments_to_text(&segments),
+        };
+        if TEXT_FORMATS.contains(&ext) || !EXPORT_FORMATS.contains(&ext) {
+            self.line_ending.apply(&contents)
+        } else {
+            contents
+        }
+    }
+
+    /// Export formats that fit the current settings; subtitles need timestamps
+    fn export_formats(&self) -> Vec<&'static str> {
+        EXPORT_FORMATS
+            .iter()
+            .copied()
+            .filter(|ext| !(self.options.no_timestamps && TIMED_FORMATS.contains(ext)))
+            .collect()
+    }
+
+    fn save_to_file(&mut self) {
+        let Some((path, ext)) = self.pick_export_file() else {
+            return;
+        };
+        if std::fs::write(&path, self.export_contents(&ext)).is_ok() {
+            self.run_post_command(&path);
+            self.delete_transcribed_audio();
+        }
+    }
+
+    /// Save the ticked segments, e.g. a few quotes; the post-save command and audio deletion
+    /// are left for the full transcript
+    fn save_checked(&mut self) {
+        let Some((path, ext)) = self.pick_export_file() else {
+            return;
+        };
+        if let Err(e) = std::fs::write(&path, self.export_checked(&ext)) {
+            self.transcribe_warning = Some(format!("Couldn't save {}: {}", path.display(), e));
+        }
+    }
+
+    /// Ask where to save, offering the formats that fit the settings; the extension picks the format
+    fn pick_export_file(&self) -> Option<(PathBuf, String)> {
+        let mut dialog = rfd::FileDialog::new().add_filter("Text", &["txt"]);
+        if !self.options.no_timestamps {
+            dialog = dialog
+                .add_filter("SubRip Subtitles", &["srt"])
+                .add_filter("WebVTT Subtitles", &["vtt"]);
+        }
+        let path = dialog
+            .add_filter("JSON", &["json"])
+            .add_filter("JSON Lines", &["jsonl"])
+            .set_file_name(self.default_file_name("txt"))
+            .save_file()?;
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        Some((path, ext))
+    }
+
+    /// Write chapters split at pauses in the speech to a sidecar file; the audio itself is never touched
+    fn save_chapters(&mut self) {
+        let stem = self
+            .audio_path
+            .as_deref()
+            .map_or_else(|| "transcription".to_string(), |p| file_stem_or(p, "transcription"));
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Podcast chapters (JSON)", &["json"])
+            .add_filter("FFmpeg metadata", &["ffmetadata"])
+            .set_file_name(format!("{}.chapters.json", stem))
+            .save_file()
+        else {
+            return;
+        };
+        let chapters = chapters_from_gaps(&self.output_segments(), (self.chapter_gap * 100.0) as i64);
+        let contents = match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("ffmetadata") => chapters_to_ffmetadata(&chapters),
+            _ => chapters_to_json(&chapters),
+        };
+        if let Err(e) = std::fs::write(&path, contents) {
+            self.transcribe_warning = Some(format!("Couldn't save {}: {}", path.display(), e));
+        }
+    }
+
+    /// Launch the user's post-processing command on a saved file, if enabled
+    fn run_post_command(&self, file: &Path) {
+        if !self.post_command_enabled || self.post_command.trim().is_empty() {
+            return;
+        }
+        let _ = spawn_post_command(&self.post_command, file);
+    }
+
+    /// Write every export format into one folder, named after the source audio file
+    fn export_all_formats(&mut self) {
+        let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+        let mut text_file = None;
+        for ext in self.export_formats() {
+            if let Ok(name) = self.output_file_name(ext) {
+                let path = dir.join(name);
+                if std::fs::write(&path, self.export_contents(ext)).is_ok() && ext == "txt" {
+                    text_file = Some(path);
+                }
+            }
+        }
+        // The command gets the plain-text transcript once, not every format
+        if let Some(path) = text_file {
+            self.run_post_command(&path);
+            self.delete_transcribed_audio();
+        }
+    }
+
+    /// Name for automatically saved output, from the user's template
+    fn output_file_name(&self, ext: &str) -> Result<String> {
+        self.output_file_name_for(self.audio_path.as_deref(), self.detected_language.as_deref(), ext)
+    }
+
+    fn output_file_name_for(&self, audio: Option<&Path>, detected_language: Option<&str>, ext: &str) -> Result<String> {
+        let stem = audio.map_or_else(|| "transcription".to_string(), |p| file_stem_or(p, "transcription"));
+        let model = self
+            .model_path
+            .as_deref()
+            .map_or_else(|| "model".to_string(), |p| file_stem_or(p, "model"));
+        let lang = self
+            .options
+            .language
+            .clone()
+            .or_else(|| detected_language.and_then(language_code))
+            .unwrap_or_else(|| "auto".to_string());
+        expand_output_template(&self.output_template, &stem, &model, &lang, ext)
+    }
+
+    /// Segments that pass the confidence filter, with low-confidence ones marked if requested,
+    /// and text cleanup applied when enabled
+    fn output_segments(&self) -> Vec<Segment> {
+        self.prepare_segments(&self.segments, self.detected_language.as_deref())
+    }
+
+    fn prepare_segments(&self, segments: &[Segment], detected_language: Option<&str>) -> Vec<Segment> {
+        let text_language = self.text_language(detected_language);
+        let segments = if self.trim_trailing_phrases {
+            let phrases: Vec<&str> = self.trailing_phrases.lines().collect();
+            &segments[..segments.len() - trailing_phrase_count(segments, &phrases)]
+        } else {
+            segments
+        };
+        let mut sentence_start = true;
+        segments
+            .iter()
+            .filter_map(|s| {
+                let mut segment = match self.min_confidence {
+                    Some(threshold) if s.confidence < threshold => {
+                        if !self.mark_low_confidence {
+                            return None;
+                        }
+                        let mut marked = s.clone();
+                        marked.text = format!(" [?] {}", s.text.trim());
+                        marked
+                    }
+                    _ => s.clone(),
+                };
+                if self.normalize_text {
+                    segment.text = normalize_text(&segment.text, text_language);
+                }
+                if self.fix_capitalization {
+                    (segment.text, sentence_start) =
+                        fix_capitalization(&segment.text, sentence_start, text_language);
+                }
+                Some(segment)
+            })
+            .collect()
+    }
+
+    /// Language of the transcript for text cleanup: the chosen one, else whisper's detection
+    fn text_language(&self, detected_language: Option<&str>) -> TextLanguage {
+        let name = match self.options.language.as_deref() {
+            Some(code) => language_name(code),
+            None => detected_language.map(str::to_string),
+        };
+        match name.map(|n| n.to_lowercase()).as_deref() {
+            Some("english") => TextLanguage::English,
+            Some("french") => TextLanguage::French,
+            _ => TextLanguage::Other,
+        }
+    }
+
+    fn refresh_transcription(&mut self) {
+        self.transcription = segments_to_text(&self.output_segments());
+    }
+
+    /// Decoded samples of the transcribed file, if the preview has them cached
+    fn playable_audio(&self) -> Option<Arc<DecodedAudio>> {
+        let cache = self.audio_cache.as_ref()?;
+        (self.audio_parts.len() <= 1 && self.audio_path.as_ref() == Some(&cache.path)).then(|| cache.audio.clone())
+    }
+
+    /// Play one segment's audio, or stop it if it's the one playing
+    fn toggle_segment_playback(&mut self, idx: usize) {
+        if self.playing_segment == Some(idx) {
+            self.stop_playback();
+            return;
+        }
+        let (Some(segment), Some(audio)) = (self.segments.get(idx), self.playable_audio()) else {
+            return;
+        };
+        let sample = |cs: i64| {
+            let cs = uncut_timestamp(cs.max(0), &self.run_cuts);
+            (cs as usize * WHISPER_SAMPLE_RATE / 100).min(audio.samples.len())
+        };
+        let (from, to) = (sample(segment.start), sample(segment.end));
+        if self.player.is_none() {
+            match Player::new() {
+                Ok(player) => self.player = Some(player),
+                Err(e) => {
+                    self.transcribe_warning = Some(format!("Couldn't play audio: {:#}", e));
+                    return;
+                }
+            }
+        }
+        if let Some(ref mut player) = self.player {
+            match player.play(&audio.samples[from..to.max(from)]) {
+                Ok(()) => self.playing_segment = Some(idx),
+                Err(e) => self.transcribe_warning = Some(format!("Couldn't play audio: {:#}", e)),
+            }
+        }
+    }
+
+    fn stop_playback(&mut self) {
+        if let Some(ref mut player) = self.player {
+            player.stop();
+        }
+        self.playing_segment = None;
+    }
+
+    /// Model picker for downloads, with progress and the outcome of the last download
+    fn download_ui(&mut self, ui: &mut egui::Ui, lang: UiLanguage) {
+        control_row(ui, self.compact, |ui| {
+            ui.label(tr(lang, "Download Model:"));
+            egui::ComboBox::from_id_salt("model_select")
+                .selected_text(&self.models[self.selected_model_idx].name)
+                .show_ui(ui, |ui| {
+                    for (idx, model) in self.models.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected_model_idx, idx, model_label(model, Some(lang)));
+                    }
+                });
+
+            let is_downloading = self.download_receiver.is_some();
+            if ui.add_enabled(!is_downloading, egui::Button::new(tr(lang, "Download"))).clicked() {
+                self.start_download();
+            }
+        });
+        if let Some(ref warning) = self.model_list_warning {
+            ui.colored_label(egui::Color32::from_rgb(255, 152, 0), warning);
+        }
+
+        // Download progress bar
+        match &self.download_status {
+            DownloadStatus::Downloading { downloaded, total } if *total == 0 => {
+                // Server didn't send a content length; show an animated bar with size and speed
+                let downloaded_mb = *downloaded as f64 / 1_000_000.0;
+                let elapsed = self
+                    .download_started
+                    .map(|t| t.elapsed().as_secs_f64())
+                    .unwrap_or(0.0);
+                let text = if elapsed > 0.5 {
+                    format!("{:.1} MB ({:.1} MB/s)", downloaded_mb, downloaded_mb / elapsed)
+                } else {
+                    format!("{:.1} MB", downloaded_mb)
+                };
+                ui.add(egui::ProgressBar::new(0.0).animate(true).text(text));
+            }
+            DownloadStatus::Downloading { downloaded, total } => {
+                let progress = *downloaded as f32 / *total as f32;
+                let downloaded_mb = *downloaded as f64 / 1_000_000.0;
+                let total_mb = *total as f64 / 1_000_000.0;
+                ui.add(egui::ProgressBar::new(progress).text(format!(
+                    "{:.1} MB / {:.1} MB ({:.0}%)",
+                    downloaded_mb,
+                    total_mb,
+                    progress * 100.0
+                )));
+            }
+            DownloadStatus::Done => {
+                ui.colored_label(egui::Color32::from_rgb(76, 175, 80), tr(lang, "Download complete!"));
+            }
+            DownloadStatus::Verifying => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr(lang, "Checking the downloaded model loads..."));
+                });
+            }
+            DownloadStatus::Error(e) => {
+                ui.colored_label(egui::Color32::RED, format!("{} {}", tr(lang, "Download error:"), e));
+            }
+            DownloadStatus::Invalid(e) => {
+                let mut redownload = false;
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!("{} {}", tr(lang, "Downloaded model is damaged:"), e),
+                    );
+                    redownload = ui.button(tr(lang, "Re-download")).clicked();
+                });
+                if redownload {
+                    self.start_download();
+                }
+            }
+            DownloadStatus::Idle => {}
+        }
+        if let (DownloadStatus::Downloading { .. }, Some((host, url))) =
+            (&self.download_status, &self.download_source)
+        {
+            ui.weak(format!("{} {}", tr(lang, "Downloading from"), host))
+                .on_hover_text(url);
+        }
+    }
+
+    /// One row per segment: play and re-transcribe buttons, start time and its text, editable.
+    /// Edited segments get a dot and a button that restores whisper's text.
+    fn segment_editor_ui(&mut self, ui: &mut egui::Ui, lang: UiLanguage, height: f32) {
+        if self.playing_segment.is_some() {
+            if self.player.as_ref().is_some_and(Player::is_playing) {
+                ui.ctx().request_repaint_after(Duration::from_millis(100));
+            } else {
+                self.playing_segment = None;
+            }
+        }
+        let can_play = self.playable_audio().is_some();
+        let can_retranscribe =
+            self.model_path.is_some() && self.receiver.is_none() && self.splice_receiver.is_none();
+        let splicing = self.splice_receiver.is_some().then_some(self.splice_segment);
+        let mut play = None;
+        let mut retranscribe = None;
+        let mut edited = false;
+        let original_text = &self.original_text;
+        egui::ScrollArea::vertical()
+            .id_salt("segment_editor")
+            .max_height(height)
+            .show(ui, |ui| {
+                for (idx, segment) in self.segments.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        let icon = if self.playing_segment == Some(idx) { "⏹" } else { "▶" };
+                        if ui
+                            .add_enabled(can_play, egui::Button::new(icon).small())
+                            .on_hover_text(tr(lang, "Play this segment"))
+                            .on_disabled_hover_text(tr(lang, "Playback needs the audio preview to finish loading"))
+                            .clicked()
+                        {
+                            play = Some(idx);
+                        }
+                        if splicing == Some(idx) {
+                            ui.spinner();
+                        } else if ui
+                            .add_enabled(can_retranscribe, egui::Button::new("⟳").small())
+                            .on_hover_text(tr(
+                                lang,
+                                "Transcribe this segment again with the current settings and replace its text",
+                            ))
+                            .clicked()
+                        {
+                            retranscribe = Some(idx);
+                        }
+                        ui.monospace(format_clock(segment.start as f32 / 100.0));
+                        let original = original_text.get(idx).filter(|o| **o != segment.text);
+                        match original {
+                            Some(original) => {
+                                ui.colored_label(egui::Color32::from_rgb(255, 152, 0), "●")
+                                    .on_hover_text(format!("{} {}", tr(lang, "Edited; whisper heard:"), original.trim()));
+                                if ui
+                                    .small_button("↺")
+                                    .on_hover_text(tr(lang, "Revert to whisper's text"))
+                                    .clicked()
+                                {
+                                    segment.text = original.clone();
+                                    edited = true;
+                                }
+                            }
+                            // Same width as the dot and button, so the text boxes stay lined up
+                            None => {
+                                ui.add_space(36.0);
+                            }
+                        }
+                        edited |= ui
+                            .add(egui::TextEdit::singleline(&mut segment.text).desired_width(f32::INFINITY))
+                            .changed();
+                    });
+                }
+            });
+        if edited {
+            self.refresh_transcription();
+        }
+        if let Some(idx) = play {
+            self.toggle_segment_playback(idx);
+        }
+        if let Some(idx) = retranscribe {
+            self.retranscribe_segment(idx);
+        }
+    }
+
+    /// The transcript as one focusable, selectable line per segment, so screen readers can
+    /// read it a segment at a time; egui moves focus between the lines with Tab and the arrows
+    fn segment_list_ui(&mut self, ui: &mut egui::Ui, lang: UiLanguage, height: f32) {
+        let segments = self.output_segments();
+        let mut copy = None;
+        // Stale ticks from before a settings change hid some segments
+        self.checked_segments.retain(|idx| *idx < segments.len());
+        ui.horizontal(|ui| {
+            let any_checked = !self.checked_segments.is_empty();
+            ui.label(format!("{} {}", self.checked_segments.len(), tr(lang, "checked")));
+            if ui.add_enabled(any_checked, egui::Button::new(tr(lang, "Copy checked"))).clicked() {
+                let text = self.export_checked("txt");
+                self.copy_text(&text);
+            }
+            if ui.add_enabled(any_checked, egui::Button::new(tr(lang, "Save checked..."))).clicked() {
+                self.save_checked();
+            }
+            if ui.add_enabled(any_checked, egui::Button::new(tr(lang, "Uncheck all"))).clicked() {
+                self.checked_segments.clear();
+            }
+        });
+        egui::ScrollArea::vertical()
+            .id_salt("segment_list")
+            .max_height(height)
+            .show(ui, |ui| {
+                for (idx, segment) in segments.iter().enumerate() {
+                    let mut line = format!(
+                        "{} – {}   {}",
+                        format_clock(segment.start as f32 / 100.0),
+                        format_clock(segment.end as f32 / 100.0),
+                        segment.text.trim()
+                    );
+                    if self.show_segment_wpm {
+                        if let Some(wpm) = words_per_minute(std::slice::from_ref(segment)) {
+                            line.push_str(&format!("   ({:.0} wpm)", wpm));
+                        }
+                    }
+                    let response = ui
+                        .horizontal(|ui| {
+                            let mut checked = self.checked_segments.contains(&idx);
+                            if ui.checkbox(&mut checked, "").changed() {
+                                if checked {
+                                    self.checked_segments.insert(idx);
+                                } else {
+                                    self.checked_segments.remove(&idx);
+                                }
+                            }
+                            let response = ui.selectable_label(self.selected_segment == Some(idx), line);
+                            if self.show_debug_info {
+                                ui.weak(format!("no speech {:.2}", segment.no_speech_prob))
+                                    .on_hover_text(tr(
+                                        lang,
+                                        "Whisper's estimate that this part held no speech; high values on real text hint at a made-up segment",
+                                    ));
+                            }
+                            response
+                        })
+                        .inner;
+                    if response.gained_focus() {
+                        response.scroll_to_me(None);
+                    }
+                    if response.clicked() || response.gained_focus() {
+                        self.selected_segment = Some(idx);
+                    }
+                    if response.has_focus() && ui.input(|i| i.events.iter().any(|e| matches!(e, egui::Event::Copy))) {
+                        copy = Some(idx);
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button(tr(lang, "Copy")).clicked() {
+                            copy = Some(idx);
+                            ui.close_menu();
+                        }
+                    });
+                }
+            });
+        if let Some(idx) = copy {
+            self.copy_text(segments[idx].text.trim());
+        }
+    }
+
+    /// Output segments merged and split to the configured subtitle limits
+    fn subtitle_segments(&self, mut segments: Vec<Segment>) -> Vec<Segment> {
+        if self.merge_segments {
+            segments = merge_segments(
+                &segments,
+                (self.merge_min_duration * 100.0) as i64,
+                self.merge_max_chars,
+            );
+        }
+        if let Some(max) = self.max_segment_duration {
+            segments = split_long_segments(&segments, (max * 100.0) as i64);
+        }
+        segments
+    }
+
+    /// Queue of files to transcribe in turn, where to save them, and progress
+    fn batch_ui(&mut self, ui: &mut egui::Ui, lang: UiLanguage) {
+        let running = self.batch_receiver.is_some();
+        ui.label(tr(lang, "Transcribes each queued file in turn with the current model and settings."));
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!running, egui::Button::new(tr(lang, "Add files..."))).clicked() {
+                if let Some(paths) = rfd::FileDialog::new()
+                    .add_filter("Audio Files", &dialog_extensions(AUDIO_EXTENSIONS))
+                    .pick_files()
+                {
+                    self.add_batch_files(paths);
+                }
+            }
+            if ui
+                .add_enabled(!running && !self.batch.is_empty(), egui::Button::new(tr(lang, "Clear list")))
+                .clicked()
+            {
+                self.batch.clear();
+                self.batch_note = None;
+                self.batch_deleted = 0;
+            }
+        });
+
+        ui.add_enabled_ui(!running, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.batch_individual_files, tr(lang, "Save each transcript as"));
+                egui::ComboBox::from_id_salt("batch_format")
+                    .selected_text(format!(".{}", self.batch_format))
+                    .show_ui(ui, |ui| {
+                        for ext in self.export_formats() {
+                            ui.selectable_value(&mut self.batch_format, ext.to_string(), format!(".{}", ext));
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.batch_combined, tr(lang, "Combine all transcripts into one document"));
+                egui::ComboBox::from_id_salt("batch_combined_format")
+                    .selected_text(self.batch_combined_format.label())
+                    .show_ui(ui, |ui| {
+                        for format in CombinedFormat::ALL {
+                            ui.selectable_value(&mut self.batch_combined_format, format, format.label());
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(lang, "Sample:"));
+                egui::ComboBox::from_id_salt("batch_sample")
+                    .selected_text(tr(lang, self.batch_sample.label()))
+                    .show_ui(ui, |ui| {
+                        for sample in [BatchSample::EveryNth(10), BatchSample::RandomPercent(10)] {
+                            let selected =
+                                std::mem::discriminant(&self.batch_sample) == std::mem::discriminant(&sample);
+                            if ui.selectable_label(selected, tr(lang, sample.label())).clicked() && !selected {
+                                self.batch_sample = sample;
+                            }
+                        }
+                    });
+                match self.batch_sample {
+                    BatchSample::EveryNth(ref mut n) => {
+                        ui.add(egui::DragValue::new(n).range(2..=1000).prefix("N = "));
+                    }
+                    BatchSample::RandomPercent(ref mut percent) => {
+                        ui.add(egui::DragValue::new(percent).range(1..=99).suffix("%"));
+                    }
+                }
+                let pending = self
+                    .batch
+                    .iter()
+                    .filter(|item| matches!(item.status, BatchStatus::Pending))
+                    .count();
+                if ui
+                    .add_enabled(pending > 1, egui::Button::new(tr(lang, "Apply")))
+                    .on_hover_text(tr(lang, "Remove the waiting files outside the sample from the queue"))
+                    .clicked()
+                {
+                    self.sample_batch();
+                }
+            });
+        });
+
+        if !self.batch.is_empty() {
+            // Waiting files can be dragged into a new order or removed; the rest stay put
+            let mut moved: Option<(usize, usize)> = None;
+            let mut removed: Option<usize> = None;
+            egui::ScrollArea::vertical()
+                .id_salt("batch_queue")
+                .max_height(150.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("batch_grid").striped(true).num_columns(3).show(ui, |ui| {
+                        for (idx, item) in self.batch.iter().enumerate() {
+                            let pending = matches!(item.status, BatchStatus::Pending);
+                            let name = if pending {
+                                ui.dnd_drag_source(egui::Id::new(("batch_item", idx)), idx, |ui| {
+                                    ui.label(format!("☰ {}", item.name()));
+                                })
+                                .response
+                            } else {
+                                ui.label(item.name())
+                            };
+                            let name = if pending {
+                                name.on_hover_text(format!("{}\n{}", full_path(&item.path), tr(lang, "Drag to reorder")))
+                            } else {
+                                name.on_hover_text(full_path(&item.path))
+                            };
+                            if pending {
+                                // The dragged file takes this one's place, so mark the side it lands on
+                                if let Some(from) = name.dnd_hover_payload::<usize>() {
+                                    let rect = name.rect;
+                                    let y = if *from < idx { rect.bottom() } else { rect.top() };
+                                    ui.painter().hline(rect.x_range(), y, ui.visuals().selection.stroke);
+                                }
+                                if let Some(from) = name.dnd_release_payload::<usize>() {
+                                    moved = Some((*from, idx));
+                                }
+                            }
+                            match item.status {
+                                BatchStatus::Pending => {
+                                    ui.weak(tr(lang, "Waiting"));
+                                }
+                                BatchStatus::Running => {
+                                    ui.horizontal(|ui| {
+                                        ui.spinner();
+                                        ui.label(tr(lang, "Transcribing..."));
+                                    });
+                                }
+                                BatchStatus::Done(_) => {
+                                    ui.colored_label(egui::Color32::from_rgb(76, 175, 80), tr(lang, "Done"));
+                                }
+                                BatchStatus::Failed(ref e) => {
+                                    ui.colored_label(egui::Color32::RED, tr(lang, "Failed")).on_hover_text(e);
+                                }
+                                BatchStatus::Cancelled => {
+                                    ui.colored_label(egui::Color32::from_rgb(255, 152, 0), tr(lang, "Cancelled"));
+                                }
+                            }
+                            if pending && ui.small_button("✕").on_hover_text(tr(lang, "Remove from queue")).clicked() {
+                                removed = Some(idx);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+
+            if let Some((from, to)) = moved {
+                if from != to && matches!(self.batch.get(from).map(|i| &i.status), Some(BatchStatus::Pending)) {
+                    let item = self.batch.remove(from);
+                    self.batch.insert(to, item);
+                }
+            }
+            if let Some(idx) = removed {
+                self.batch.remove(idx);
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let has_pending = self
+                .batch
+                .iter()
+                .any(|item| matches!(item.status, BatchStatus::Pending));
+            let can_start = self.model_path.is_some()
+                && has_pending
+                && !running
+                && self.receiver.is_none()
+                && self.batch_estimate_receiver.is_none()
+                && (self.batch_individual_files || self.batch_combined);
+            if ui
+                .add_enabled(can_start, egui::Button::new(tr(lang, "Start batch...")))
+                .on_hover_text(tr(lang, "Choose a folder for the transcripts, then start"))
+                .clicked()
+            {
+                self.start_batch();
+            }
+            if running && ui.button(tr(lang, "Stop")).clicked() {
+                self.batch_cancel.store(true, Ordering::Relaxed);
+            }
+            if let Some(ref note) = self.batch_note {
+                ui.label(note);
+            }
+        });
+    }
+
+    /// Transcript text, GPU/CPU disagreements and the output buttons
+    fn output_ui(&mut self, ui: &mut egui::Ui, lang: UiLanguage) {
+        ui.horizontal(|ui| {
+            ui.label(tr(lang, "Transcription:"));
+            let label = if self.output_maximized {
+                tr(lang, "Show all controls")
+            } else {
+                tr(lang, "Maximize output")
+            };
+            if ui.small_button(label).clicked() {
+                self.output_maximized = !self.output_maximized;
+            }
+            ui.separator();
+            let previous = self.output_view;
+            ui.selectable_value(&mut self.output_view, OutputView::Text, tr(lang, "Text"));
+            ui.selectable_value(&mut self.output_view, OutputView::List, tr(lang, "Segments"))
+                .on_hover_text(tr(
+                    lang,
+                    "One line per segment with its times; Tab or the arrow keys move between them and Ctrl+C copies one",
+                ));
+            let can_edit = !self.segments.is_empty() && self.receiver.is_none();
+            if ui
+                .add_enabled(
+                    can_edit,
+                    egui::SelectableLabel::new(self.output_view == OutputView::Edit, tr(lang, "Edit")),
+                )
+                .on_hover_text(tr(lang, "Correct the text segment by segment, playing each one back"))
+                .clicked()
+            {
+                self.output_view = OutputView::Edit;
+            }
+            if previous == OutputView::Edit && self.output_view != OutputView::Edit {
+                self.stop_playback();
+            }
+            if self.output_view == OutputView::List {
+                ui.checkbox(&mut self.show_segment_wpm, tr(lang, "Words per minute"))
+                    .on_hover_text(tr(lang, "Speaking rate of each segment, to spot rushed or slow passages"));
+            }
+        });
+
+        // Fill the window down to the buttons, keeping room for about ten lines
+        let reserved = if self.disagreements.as_ref().is_some_and(|d| !d.is_empty()) {
+            240.0
+        } else {
+            60.0
+        };
+        let height = (ui.available_height() - reserved).max(250.0);
+
+        let has_segments = !self.segments.is_empty();
+        if self.output_view == OutputView::Edit && has_segments && self.receiver.is_none() {
+            self.segment_editor_ui(ui, lang, height);
+        } else if self.output_view == OutputView::List && has_segments {
+            self.segment_list_ui(ui, lang, height);
+        } else {
+            let no_speech = self.segments.iter().all(|s| s.text.trim().is_empty());
+            let hint = if self.status == TranscribeStatus::Done && no_speech {
+                tr(lang, "No speech detected")
+            } else {
+                ""
+            };
+            // Follows new streamed text while at the bottom; scrolling up stops following
+            egui::ScrollArea::vertical()
+                .max_height(height)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.transcription.as_str())
+                            .hint_text(hint)
+                            .desired_width(f32::INFINITY)
+                            .desired_rows(10)
+                            .min_size(egui::vec2(0.0, height)),
+                    );
+                });
+        }
+
+        if let Some(ref disagreements) = self.disagreements {
+            if disagreements.is_empty() {
+                ui.colored_label(
+                    egui::Color32::from_rgb(76, 175, 80),
+                    tr(lang, "GPU and CPU transcripts match"),
+                );
+            } else {
+                egui::CollapsingHeader::new(format!(
+                    "{} ({})",
+                    tr(lang, "Segments that differ between GPU and CPU"),
+                    disagreements.len()
+                ))
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .id_salt("disagreements")
+                        .max_height(150.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("disagreements_grid").striped(true).show(ui, |ui| {
+                                ui.strong(tr(lang, "Time"));
+                                ui.strong("GPU");
+                                ui.strong("CPU");
+                                ui.end_row();
+                                for d in disagreements {
+                                    ui.label(format!(
+                                        "{} - {}",
+                                        format_clock(d.start as f32 / 100.0),
+                                        format_clock(d.end as f32 / 100.0)
+                                    ));
+                                    ui.label(d.gpu_text.trim());
+                                    ui.label(d.cpu_text.trim());
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                });
+            }
+        }
+
+        ui.add_space(10.0);
+
+        // Action buttons
+        ui.horizontal(|ui| {
+            let copy = ui
+                .add_enabled(!self.transcription.is_empty(), egui::Button::new(tr(lang, "Copy")))
+                .on_hover_text(tr(lang, "Right-click to copy with the file name and length on top"));
+            if copy.clicked() {
+                self.copy_to_clipboard();
+            }
+            copy.context_menu(|ui| {
+                if ui.button(tr(lang, "Copy with file name")).clicked() {
+                    self.copy_with_source_header();
+                    ui.close_menu();
+                }
+            });
+            if ui
+                .add_enabled(!self.transcription.is_empty(), egui::Button::new(tr(lang, "Save...")))
+                .clicked()
+            {
+                self.save_to_file();
+            }
+            if ui
+                .add_enabled(!self.transcription.is_empty(), egui::Button::new(tr(lang, "Export all...")))
+                .on_hover_text(if self.options.no_timestamps {
+                    tr(lang, "Write .txt, .json and .jsonl into a folder")
+                } else {
+                    tr(lang, "Write .txt, .srt, .vtt, .json and .jsonl into a folder")
+                })
+                .clicked()
+            {
+                self.export_all_formats();
+            }
+            if ui
+                .add_enabled(
+                    !self.transcription.is_empty() && !self.options.no_timestamps,
+                    egui::Button::new(tr(lang, "Save chapters...")),
+                )
+                .on_hover_text(tr(
+                    lang,
+                    "Chapter markers at pauses in the speech, as podcast JSON or FFmpeg metadata; the audio file isn't changed",
+                ))
+                .clicked()
+            {
+                self.save_chapters();
+            }
+            if ui.button(tr(lang, "Clear")).clicked() {
+                let cleared = ClearedOutput {
+                    segments: std::mem::take(&mut self.segments),
+                    original_text: std::mem::take(&mut self.original_text),
+                    status: std::mem::replace(&mut self.status, TranscribeStatus::Idle),
+                    warning: self.transcribe_warning.take(),
+                    stats: self.last_stats.take(),
+                };
+                if !cleared.segments.is_empty() {
+                    self.cleared = Some(cleared);
+                }
+                self.disagreements = None;
+                self.checked_segments.clear();
+                self.transcription.clear();
+            }
+            // Only offered while the output is still empty, so undo never overwrites new results
+            if self.segments.is_empty() && self.receiver.is_none() && self.cleared.is_some() {
+                if ui.button(tr(lang, "Undo clear")).clicked() {
+                    if let Some(cleared) = self.cleared.take() {
+                        self.segments = cleared.segments;
+                        self.original_text = cleared.original_text;
+                        self.status = cleared.status;
+                        self.transcribe_warning = cleared.warning;
+                        self.last_stats = cleared.stats;
+                        self.refresh_transcription();
+                    }
+                }
+            }
+
+            if let Some((ref result, at)) = self.copy_feedback {
+                let remaining = COPY_FEEDBACK_DURATION.saturating_sub(at.elapsed());
+                if remaining.is_zero() {
+                    self.copy_feedback = None;
+                } else {
+                    match result {
+                        Ok(()) => {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(76, 175, 80),
+                                tr(lang, "Copied!"),
+                            );
+                        }
+                        Err(e) => {
+                            ui.colored_label(egui::Color32::RED, tr(lang, "Clipboard unavailable"))
+                                .on_hover_text(e);
+                        }
+                    }
+                    ui.ctx().request_repaint_after(remaining);
+                }
+            }
+        });
+    }
+}
+
+impl eframe::App for WhisperApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, SETTINGS_KEY, &self.settings());
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // A download still running is abandoned, so its partial file goes too
+        self.download_cancel.store(true, Ordering::Relaxed);
+        remove_temp_files(self.temp_dir.as_deref());
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.check_messages();
+        self.check_splice_messages();
+        self.check_download_messages();
+        self.check_benchmark_messages();
+        self.check_self_test();
+        self.check_batch_messages();
+        self.check_waveform_messages();
+        self.check_incoming_files(ctx);
+
+        // Handle dropped files
+        let mut dropped_audio: Option<PathBuf> = None;
+        let mut dropped_model: Option<PathBuf> = None;
+        let mut paste_files = false;
+        let nothing_focused = ctx.memory(|m| m.focused().is_none());
+        ctx.input(|i| {
+            // A pasted file path (e.g. Explorer's "Copy as path") selects that audio file
+            if nothing_focused {
+                for event in &i.events {
+                    match event {
+                        egui::Event::Paste(text) => {
+                            if let Some(path) = audio_path_from_text(text) {
+                                dropped_audio = Some(path);
+                            }
+                        }
+                        // Files copied in the file manager put no text on the clipboard, so the
+                        // shortcut gives no Paste event; only its key release comes through
+                        egui::Event::Key {
+                            key: egui::Key::V,
+                            pressed: false,
+                            modifiers,
+                            ..
+                        } if modifiers.command => paste_files = true,
+                        _ => {}
+                    }
+                }
+            }
+
+            for file in &i.raw.dropped_files {
+                if let Some(path) = &file.path {
+                    if is_model_file(path) {
+                        dropped_model = Some(path.clone());
+                    } else if is_audio_file(path) {
+                        dropped_audio = Some(path.clone());
+                    }
+                }
+            }
+        });
+        if let Some(path) = dropped_model {
+            self.set_model_path(path);
+        }
+        if let Some(path) = dropped_audio {
+            self.set_audio_path(path);
+        }
+        if paste_files {
+            self.paste_audio_from_clipboard();
+        }
+
+        // Request repaint while processing
+        if self.receiver.is_some()
+            || self.splice_receiver.is_some()
+            || self.download_receiver.is_some()
+            || self.benchmark_receiver.is_some()
+            || self.self_test_receiver.is_some()
+            || self.batch_receiver.is_some()
+            || self.batch_estimate_receiver.is_some()
+            || self.waveform_receiver.is_some()
+        {
+            ctx.request_repaint();
+        }
+
+        if self.show_setup_wizard {
+            self.setup_wizard(ctx);
+        }
+        if self.confirm_delete_audio {
+            self.confirm_delete_audio_ui(ctx);
+        }
+        if self.language_mismatch.is_some() {
+            self.language_mismatch_ui(ctx);
+        }
+        if self.batch_estimate.is_some() || self.batch_estimate_receiver.is_some() {
+            self.confirm_batch_ui(ctx);
+        }
+        if self.unfinished_batch.is_some() {
+            self.resume_batch_ui(ctx);
+        }
+
+        let lang = self.ui_language;
+        self.compact = ctx.screen_rect().width() < COMPACT_WIDTH;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.output_maximized {
+                self.output_ui(ui, lang);
+                return;
+            }
+
+            if !self.compact {
+                ui.heading(tr(lang, APP_NAME));
+                ui.add_space(10.0);
+            }
+
+            // Model download section; folded away when the window is narrow
+            if self.compact {
+                egui::CollapsingHeader::new(tr(lang, "Download Model"))
+                    .id_salt("download_section")
+                    .show(ui, |ui| self.download_ui(ui, lang));
+            } else {
+                self.download_ui(ui, lang);
+            }
+
+            ui.add_space(5.0);
+
+            // Model selection (browse or use downloaded)
+            control_row(ui, self.compact, |ui| {
+                ui.label(tr(lang, "Model:"));
+                if let Some(ref path) = self.model_path {
+                    ui.label(path.file_name().unwrap_or_default().to_string_lossy())
+                        .on_hover_text(full_path(path));
+                } else {
+                    ui.label(tr(lang, "(none)"));
+                }
+                if ui.button(tr(lang, "Browse...")).clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Whisper Model", &dialog_extensions(&["bin"]))
+                        .pick_file()
+                    {
+                        self.set_model_path(path);
+                    }
+                }
+            });
+
+            ui.add_space(5.0);
+
+            // Audio selection
+            control_row(ui, self.compact, |ui| {
+                ui.label(tr(lang, "Audio:"));
+                if self.audio_parts.len() > 1 {
+                    let names: Vec<String> = self
+                        .audio_parts
+                        .iter()
+                        .map(|p| p.file_name().unwrap_or_default().to_string_lossy().into_owned())
+                        .collect();
+                    ui.label(format!("{} + {} more (joined)", names[0], names.len() - 1))
+                        .on_hover_text(names.join("\n"));
+                } else if let Some(ref path) = self.audio_path {
+                    ui.label(path.file_name().unwrap_or_default().to_string_lossy())
+                        .on_hover_text(full_path(path));
+                    if self.audio_reused {
+                        ui.weak(tr(lang, "(unchanged, not decoded again)"));
+                    }
+                } else {
+                    ui.label(tr(lang, "(none)"));
+                }
+                if ui.button(tr(lang, "Browse...")).clicked() {
+                    if let Some(mut paths) = rfd::FileDialog::new()
+                        .add_filter("Audio Files", &dialog_extensions(AUDIO_EXTENSIONS))
+                        .pick_files()
+                    {
+                        if paths.len() > 1 {
+                            self.set_audio_parts(paths);
+                        } else if let Some(path) = paths.pop() {
+                            self.set_audio_path(path);
+                        }
+                    }
+                }
+                if ui
+                    .button(tr(lang, "Paste"))
+                    .on_hover_text(tr(lang, "Use an audio file copied in the file manager"))
+                    .clicked()
+                {
+                    self.paste_audio_from_clipboard();
+                }
+            });
+
+            // Tag info for the selected audio
+            if let Some(ref title) = self.audio_tags.title {
+                let text = match self.audio_tags.artist {
+                    Some(ref artist) => format!("{} — {}", title, artist),
+                    None => title.clone(),
+                };
+                ui.label(egui::RichText::new(text).weak());
+            }
+
+            self.waveform_ui(ui, lang);
+
+            ui.add_space(5.0);
+
+            // Named settings profiles
+            control_row(ui, self.compact, |ui| {
+                ui.label(tr(lang, "Profile:"));
+                let mut chosen: Option<String> = None;
+                egui::ComboBox::from_id_salt("profile_select")
+                    .selected_text(self.selected_profile.as_deref().unwrap_or(tr(lang, "(custom)")))
+                    .show_ui(ui, |ui| {
+                        for name in self.profiles.keys() {
+                            let selected = self.selected_profile.as_deref() == Some(name.as_str());
+                            if ui.selectable_label(selected, name).clicked() {
+                                chosen = Some(name.clone());
+                            }
+                        }
+                    });
+                if let Some(name) = chosen {
+                    self.apply_profile(&name);
+                }
+
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.new_profile_name)
+                        .hint_text(tr(lang, "New profile name"))
+                        .desired_width(120.0),
+                );
+                let name = self.new_profile_name.trim().to_string();
+                if ui.add_enabled(!name.is_empty(), egui::Button::new(tr(lang, "Save"))).clicked() {
+                    self.save_profile(name);
+                    self.new_profile_name.clear();
+                }
+                if let Some(selected) = self.selected_profile.clone() {
+                    if ui.button(tr(lang, "Delete")).clicked() {
+                        self.profiles.remove(&selected);
+                        self.selected_profile = None;
+                    }
+                }
+                ui.menu_button("⚙", |ui| {
+                    if ui.button(tr(lang, "Export settings...")).clicked() {
+                        ui.close_menu();
+                        self.export_settings();
+                    }
+                    if ui.button(tr(lang, "Import settings...")).clicked() {
+                        ui.close_menu();
+                        self.import_settings();
+                    }
+                })
+                .response
+                .on_hover_text(tr(lang, "Share transcription settings with others as a file"));
+            });
 
             ui.add_space(5.0);
 
-            // Model selection (browse or use downloaded)
-            ui.horizontal(|ui| {
-                ui.label("Model:");
-                if let Some(ref path) = self.model_path {
-                    ui.label(path.file_name().unwrap_or_default().to_string_lossy());
-                } else {
-                    ui.label("(none)");
+            // Spoken language
+            control_row(ui, self.compact, |ui| {
+                ui.label(tr(lang, "Language:"));
+                let selected = match self.options.language.as_deref() {
+                    Some(code) => language_name(code).unwrap_or_else(|| code.to_string()),
+                    None => tr(lang, "Auto-detect").to_string(),
+                };
+                egui::ComboBox::from_id_salt("language_select")
+                    .selected_text(selected)
+                    .height(300.0)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.options.language, None, tr(lang, "Auto-detect"));
+                        for (name, code) in spoken_languages() {
+                            ui.selectable_value(&mut self.options.language, Some(code.to_string()), name)
+                                .on_hover_text(code);
+                        }
+                    });
+            });
+
+            ui.add_space(10.0);
+
+            // Drag & drop hint with supported formats
+            ui.label(tr(lang, "Drag & drop audio files (MP3, WAV, FLAC, OGG, M4A, AAC, WMA, Opus)"));
+
+            ui.add_space(10.0);
+
+            // Advanced options
+            ui.collapsing(tr(lang, "Advanced"), |ui| {
+                ui.checkbox(&mut self.notify_on_done, tr(lang, "Notify when transcription finishes"));
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Theme:"));
+                    let before = self.theme;
+                    ui.selectable_value(&mut self.theme, egui::ThemePreference::System, tr(lang, "System"));
+                    ui.selectable_value(&mut self.theme, egui::ThemePreference::Dark, tr(lang, "Dark"));
+                    ui.selectable_value(&mut self.theme, egui::ThemePreference::Light, tr(lang, "Light"));
+                    if self.theme != before {
+                        ui.ctx().set_theme(self.theme);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Interface language:"));
+                    egui::ComboBox::from_id_salt("ui_language")
+                        .selected_text(self.ui_language.label())
+                        .show_ui(ui, |ui| {
+                            for language in UiLanguage::ALL {
+                                ui.selectable_value(&mut self.ui_language, language, language.label());
+                            }
+                        });
+                });
+                ui.checkbox(&mut self.options.use_gpu, tr(lang, "Use GPU when available"));
+                ui.add_enabled(
+                    self.options.use_gpu,
+                    egui::Checkbox::new(&mut self.options.verify_on_cpu, tr(lang, "Verify GPU output on CPU")),
+                )
+                .on_hover_text(tr(lang, "Transcribes twice and flags segments where the two runs differ"));
+                ui.add_enabled_ui(self.options.use_gpu, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr(lang, "GPU load timeout:"));
+                        ui.add(
+                            egui::DragValue::new(&mut self.options.gpu_timeout_secs)
+                                .range(0..=600)
+                                .suffix(" s"),
+                        )
+                        .on_hover_text(tr(
+                            lang,
+                            "Falls back to CPU when the GPU takes longer than this to load the model, e.g. after a driver crash. 0 waits forever.",
+                        ));
+                    });
+                });
+                ui.checkbox(&mut self.options.mmap_audio, tr(lang, "Memory-map audio files"))
+                    .on_hover_text(tr(lang, "Can speed up reading very large files"));
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Models folder:"));
+                    ui.label(self.models_dir.display().to_string());
+                    if ui.button(tr(lang, "Change...")).clicked() {
+                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            self.models_dir = dir;
+                            self.reload_model_list();
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Temp folder:"));
+                    match self.temp_dir {
+                        Some(ref dir) => ui.label(dir.display().to_string()),
+                        None => ui.weak(tr(lang, "system default")),
+                    }
+                    .on_hover_text(tr(lang, "Downloads are staged here before they're moved into the models folder"));
+                    if ui.button(tr(lang, "Change...")).clicked() {
+                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            self.temp_dir = Some(dir);
+                        }
+                    }
+                    if self.temp_dir.is_some() && ui.button(tr(lang, "Default")).clicked() {
+                        self.temp_dir = None;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Output name:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.output_template)
+                            .hint_text(DEFAULT_OUTPUT_TEMPLATE)
+                            .desired_width(200.0),
+                    )
+                    .on_hover_text(tr(lang, "Used by Export all; tokens: {stem} {model} {lang} {ext}"));
+                    match self.output_file_name("txt") {
+                        Ok(example) => {
+                            ui.weak(example);
+                        }
+                        Err(e) => {
+                            ui.colored_label(egui::Color32::RED, e.to_string());
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Line endings:"));
+                    egui::ComboBox::from_id_salt("line_ending")
+                        .selected_text(tr(lang, self.line_ending.label()))
+                        .show_ui(ui, |ui| {
+                            for ending in LineEnding::ALL {
+                                ui.selectable_value(&mut self.line_ending, ending, tr(lang, ending.label()));
+                            }
+                        })
+                        .response
+                        .on_hover_text(tr(lang, "Used for saved .txt, .srt and .vtt files"));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "JSON timestamps:"));
+                    egui::ComboBox::from_id_salt("json_time_unit")
+                        .selected_text(tr(lang, self.json_time_unit.label()))
+                        .show_ui(ui, |ui| {
+                            for unit in TimeUnit::ALL {
+                                ui.selectable_value(&mut self.json_time_unit, unit, tr(lang, unit.label()));
+                            }
+                        })
+                        .response
+                        .on_hover_text(tr(
+                            lang,
+                            "Unit of start and end times in .json and .jsonl files, named in each segment's \"time_unit\"",
+                        ));
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.post_command_enabled, tr(lang, "After saving, run:"))
+                        .on_hover_text(tr(lang, "Runs a shell command with {file} replaced by the saved transcript's path"));
+                    ui.add_enabled(
+                        self.post_command_enabled,
+                        egui::TextEdit::singleline(&mut self.post_command)
+                            .hint_text("python summarize.py {file}")
+                            .desired_width(240.0),
+                    );
+                });
+                let mut delete_audio = self.delete_audio_after_save;
+                if ui
+                    .checkbox(&mut delete_audio, tr(lang, "Delete audio after successful transcription"))
+                    .on_hover_text(tr(lang, "Removes the source file once its transcript is saved"))
+                    .changed()
+                {
+                    if delete_audio && !self.delete_audio_confirmed {
+                        self.confirm_delete_audio = true;
+                    } else {
+                        self.delete_audio_after_save = delete_audio;
+                    }
                 }
-                if ui.button("Browse...").clicked() {
-                    if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("Whisper Model", &["bin"])
-                        .pick_file()
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Download proxy:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.network.proxy_url)
+                            .hint_text(tr(lang, "http://proxy:8080 (default: system env)"))
+                            .desired_width(220.0),
+                    );
+                });
+                if !self.network.proxy_url.trim().is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label(tr(lang, "Proxy user:"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.network.proxy_username)
+                                .desired_width(100.0),
+                        );
+                        ui.label(tr(lang, "Password:"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.network.proxy_password)
+                                .password(true)
+                                .desired_width(100.0),
+                        );
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "CA certificate:"));
+                    match self.network.ca_cert_path {
+                        Some(ref path) => ui.label(path.display().to_string()),
+                        None => ui.weak(tr(lang, "system certificates only")),
+                    }
+                    .on_hover_text(tr(lang, "Also trust this certificate for downloads, e.g. a company proxy's that inspects TLS"));
+                    if ui.button(tr(lang, "Browse...")).clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Certificates", &dialog_extensions(&["pem", "crt", "cer", "der"]))
+                            .pick_file()
+                        {
+                            self.network.ca_cert_path = Some(path);
+                        }
+                    }
+                    if self.network.ca_cert_path.is_some() && ui.button(tr(lang, "Clear")).clicked() {
+                        self.network.ca_cert_path = None;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let mut limit_ctx = self.options.max_text_ctx.is_some();
+                    if ui
+                        .checkbox(&mut limit_ctx, tr(lang, "Limit text context (tokens):"))
+                        .on_hover_text(tr(lang, "Lower values use less memory; higher values keep more context on long audio"))
+                        .changed()
+                    {
+                        self.options.max_text_ctx = limit_ctx.then_some(16384);
+                    }
+                    if let Some(ref mut n) = self.options.max_text_ctx {
+                        ui.add(egui::DragValue::new(n).range(0..=16384).speed(16));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Initial prompt:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.options.initial_prompt)
+                            .hint_text(tr(lang, "Names, jargon or style to prime the model"))
+                            .desired_width(260.0),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    let mut set_threads = self.options.n_threads.is_some();
+                    if ui.checkbox(&mut set_threads, tr(lang, "Threads:")).changed() {
+                        self.options.n_threads = set_threads.then(|| {
+                            thread::available_parallelism().map_or(4, |n| n.get() as i32)
+                        });
+                    }
+                    if let Some(ref mut n) = self.options.n_threads {
+                        ui.add(egui::DragValue::new(n).range(1..=64));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Best of:"));
+                    ui.add(egui::DragValue::new(&mut self.options.best_of).range(1..=5))
+                        .on_hover_text(tr(lang, "Higher values are more robust but slower"));
+                    ui.label(tr(lang, "Temperature:"));
+                    ui.add(
+                        egui::DragValue::new(&mut self.options.temperature)
+                            .range(0.0..=1.0)
+                            .speed(0.05),
+                    );
+                    ui.label(tr(lang, "Fallback increment:"));
+                    ui.add(
+                        egui::DragValue::new(&mut self.options.temperature_inc)
+                            .range(0.0..=1.0)
+                            .speed(0.05),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    let mut check_silence = self.options.silence_threshold_db.is_some();
+                    if ui
+                        .checkbox(&mut check_silence, tr(lang, "Skip silent audio below (dBFS):"))
+                        .changed()
+                    {
+                        self.options.silence_threshold_db = check_silence.then_some(-60.0);
+                    }
+                    if let Some(ref mut db) = self.options.silence_threshold_db {
+                        ui.add(egui::DragValue::new(db).range(-120.0..=0.0).speed(1.0));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Auto gain:"));
+                    ui.add(egui::Slider::new(&mut self.options.auto_gain, 0.0..=1.0).step_by(0.05))
+                        .on_hover_text(tr(
+                            lang,
+                            "Lifts quiet passages, such as a speaker far from the mic, towards the level of loud ones. 0 turns it off.",
+                        ));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Audio filter:"));
+                    egui::ComboBox::from_id_salt("audio_filter")
+                        .selected_text(tr(lang, self.options.audio_filter.label()))
+                        .show_ui(ui, |ui| {
+                            for filter in AudioFilter::ALL {
+                                ui.selectable_value(&mut self.options.audio_filter, filter, tr(lang, filter.label()));
+                            }
+                        })
+                        .response
+                        .on_hover_text(tr(
+                            lang,
+                            "Filters the audio before transcribing to take out mains hum, keep only the voice band of a phone call, or soften hiss",
+                        ));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Lead-in silence:"));
+                    ui.add(
+                        egui::DragValue::new(&mut self.options.lead_in_ms)
+                            .range(0..=1000)
+                            .speed(10.0)
+                            .suffix(" ms"),
+                    )
+                    .on_hover_text(tr(
+                        lang,
+                        "Silence added before the audio so whisper doesn't clip the first word of short clips. 0 adds none.",
+                    ));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "Also transcribe with:"));
+                    let selected = self.ensemble_model.clone().unwrap_or_else(|| tr(lang, "None").to_string());
+                    egui::ComboBox::from_id_salt("ensemble_model")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.ensemble_model, None, tr(lang, "None"));
+                            for path in local_models(&self.models_dir) {
+                                let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                                ui.selectable_value(&mut self.ensemble_model, Some(name.clone()), name);
+                            }
+                        })
+                        .response
+                        .on_hover_text(tr(
+                            lang,
+                            "Transcribes a second time with this model and keeps whichever segments whisper was more confident of. Takes the time of both runs.",
+                        ));
+                });
+                ui.checkbox(&mut self.show_debug_info, tr(lang, "Show debug info"))
+                    .on_hover_text(tr(lang, "Shows each segment's no-speech probability in the Segments view"));
+                ui.horizontal(|ui| {
+                    let resampler = &mut self.options.resampler;
+                    ui.label(tr(lang, "Resampler window:"));
+                    egui::ComboBox::from_id_salt("resample_window")
+                        .selected_text(resampler.window.label())
+                        .show_ui(ui, |ui| {
+                            for window in ResampleWindow::ALL {
+                                ui.selectable_value(&mut resampler.window, window, window.label());
+                            }
+                        });
+                    ui.label(tr(lang, "Cutoff:"));
+                    ui.add(
+                        egui::DragValue::new(&mut resampler.f_cutoff)
+                            .range(0.5..=0.99)
+                            .speed(0.01),
+                    );
+                    ui.label(tr(lang, "Oversampling:"));
+                    ui.add(egui::DragValue::new(&mut resampler.oversampling_factor).range(16..=1024));
+                    if ui
+                        .add_enabled(*resampler != ResamplerOptions::default(), egui::Button::new(tr(lang, "Reset")))
+                        .clicked()
                     {
-                        self.model_path = Some(path);
+                        *resampler = ResamplerOptions::default();
+                    }
+                });
+                ui.checkbox(&mut self.options.dtw, tr(lang, "Precise word timestamps (DTW)"))
+                    .on_hover_text(tr(lang, "Align tokens to the audio so subtitles start on the right word; works with the standard tiny–large models"));
+                ui.checkbox(&mut self.options.no_timestamps, tr(lang, "Skip timestamps (text only)"))
+                    .on_hover_text(tr(lang, "Faster when you only need the text; disables SRT and VTT export"));
+                ui.checkbox(&mut self.options.show_special_tokens, tr(lang, "Show special tokens"))
+                    .on_hover_text(tr(lang, "Keep whisper's raw token markers in the output; useful when tuning prompts"));
+                let tdrz_model = self.model_path.as_deref().is_some_and(is_tinydiarize_model);
+                ui.add_enabled(
+                    tdrz_model,
+                    egui::Checkbox::new(&mut self.options.tinydiarize, tr(lang, "Mark speaker turns")),
+                )
+                .on_hover_text(tr(lang, "Start a new line when the speaker changes"))
+                .on_disabled_hover_text(tr(lang, "Needs a tinydiarize model, e.g. ggml-small.en-tdrz.bin"));
+                ui.checkbox(&mut self.options.single_segment, tr(lang, "Force single segment"))
+                    .on_hover_text(tr(lang, "Emit one segment for the whole clip; useful for short clips"));
+                ui.checkbox(&mut self.options.condition_on_previous, tr(lang, "Condition on previous text"))
+                    .on_hover_text(tr(lang, "Feed earlier text back as context; can loop on music or ASMR"));
+                ui.horizontal(|ui| {
+                    let mut filter = self.min_confidence.is_some();
+                    let mut changed = ui
+                        .checkbox(&mut filter, tr(lang, "Confidence threshold:"))
+                        .on_hover_text(tr(lang, "Leave out segments whisper was unsure about"))
+                        .changed();
+                    if changed {
+                        self.min_confidence = filter.then_some(0.5);
+                    }
+                    if let Some(ref mut threshold) = self.min_confidence {
+                        changed |= ui
+                            .add(egui::Slider::new(threshold, 0.0..=1.0).fixed_decimals(2))
+                            .changed();
+                        changed |= ui
+                            .checkbox(&mut self.mark_low_confidence, tr(lang, "Mark with [?] instead of dropping"))
+                            .changed();
+                    }
+                    if changed {
+                        self.refresh_transcription();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let mut changed = ui
+                        .checkbox(&mut self.trim_trailing_phrases, tr(lang, "Remove made-up closing phrases"))
+                        .on_hover_text(tr(lang, "Drops phrases like \"Thanks for watching!\" when whisper adds them, unsure, at the very end"))
+                        .changed();
+                    if self.trim_trailing_phrases {
+                        ui.menu_button(tr(lang, "Edit list..."), |ui| {
+                            ui.label(tr(lang, "One phrase per line; case and punctuation are ignored"));
+                            changed |= ui
+                                .add(egui::TextEdit::multiline(&mut self.trailing_phrases).desired_rows(6))
+                                .changed();
+                            if ui.button(tr(lang, "Reset")).clicked() {
+                                self.trailing_phrases = DEFAULT_TRAILING_PHRASES.to_string();
+                                changed = true;
+                            }
+                        });
+                    }
+                    if changed {
+                        self.refresh_transcription();
                     }
+                });
+                if ui
+                    .checkbox(&mut self.fix_capitalization, tr(lang, "Fix capitalization"))
+                    .on_hover_text(tr(
+                        lang,
+                        "Capital letters at the start of sentences, and \"I\" in English, for models that write in lowercase",
+                    ))
+                    .changed()
+                {
+                    self.refresh_transcription();
+                }
+                if ui
+                    .checkbox(&mut self.normalize_text, tr(lang, "Clean up numbers and punctuation"))
+                    .on_hover_text(tr(lang, "Write spelled-out numbers as digits (English) and fix spacing around punctuation"))
+                    .changed()
+                {
+                    self.refresh_transcription();
                 }
+                ui.checkbox(&mut self.merge_segments, tr(lang, "Merge short subtitle segments"));
+                ui.add_enabled_ui(self.merge_segments, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr(lang, "Min duration (s):"));
+                        ui.add(
+                            egui::DragValue::new(&mut self.merge_min_duration)
+                                .range(0.0..=30.0)
+                                .speed(0.1),
+                        );
+                        ui.label(tr(lang, "Max chars:"));
+                        ui.add(egui::DragValue::new(&mut self.merge_max_chars).range(10..=500));
+                    });
+                });
+                ui.horizontal(|ui| {
+                    let mut limit = self.max_segment_duration.is_some();
+                    if ui
+                        .checkbox(&mut limit, tr(lang, "Max subtitle duration (s):"))
+                        .on_hover_text(tr(lang, "Split longer subtitles at word boundaries"))
+                        .changed()
+                    {
+                        self.max_segment_duration = limit.then_some(7.0);
+                    }
+                    if let Some(ref mut max) = self.max_segment_duration {
+                        ui.add(egui::DragValue::new(max).range(1.0..=30.0).speed(0.1));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(lang, "New chapter after pauses of (s):"));
+                    ui.add(
+                        egui::DragValue::new(&mut self.chapter_gap)
+                            .range(0.5..=60.0)
+                            .speed(0.1),
+                    );
+                });
             });
 
-            ui.add_space(5.0);
-
-            // Audio selection
-            ui.horizontal(|ui| {
-                ui.label("Audio:");
-                if let Some(ref path) = self.audio_path {
-                    ui.label(path.file_name().unwrap_or_default().to_string_lossy());
-                } else {
-                    ui.label("(none)");
+            // Model benchmark
+            ui.collapsing(tr(lang, "Benchmark"), |ui| {
+                ui.label(tr(
+                    lang,
+                    "Times each model in the models folder on the same built-in clip, so results compare between machines.",
+                ));
+                ui.horizontal(|ui| {
+                    let idle = self.benchmark_receiver.is_none()
+                        && self.receiver.is_none()
+                        && self.batch_receiver.is_none();
+                    if ui
+                        .add_enabled(idle, egui::Button::new(tr(lang, "Run benchmark")))
+                        .clicked()
+                    {
+                        self.start_benchmark();
+                    }
+                    if self.benchmark_receiver.is_some() {
+                        if ui.button(tr(lang, "Cancel")).clicked() {
+                            self.benchmark_cancel.store(true, Ordering::Relaxed);
+                        }
+                        ui.spinner();
+                        if let Some(ref model) = self.benchmark_running {
+                            ui.label(format!("{} {}...", tr(lang, "Running"), model));
+                        }
+                    }
+                });
+                if !self.benchmark_results.is_empty() {
+                    egui::Grid::new("benchmark_results")
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            ui.strong(tr(lang, "Model"));
+                            ui.strong(tr(lang, "Time"));
+                            ui.strong(tr(lang, "Output"));
+                            ui.end_row();
+                            for result in &self.benchmark_results {
+                                ui.label(&result.model);
+                                ui.label(format!("{:.1}s", result.elapsed_secs));
+                                match result.output {
+                                    Ok(ref text) => {
+                                        let preview: String = text.chars().take(60).collect();
+                                        ui.label(preview).on_hover_text(text);
+                                    }
+                                    Err(ref e) => {
+                                        ui.colored_label(egui::Color32::RED, e);
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
                 }
-                if ui.button("Browse...").clicked() {
-                    if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("Audio Files", AUDIO_EXTENSIONS)
-                        .pick_file()
+            });
+
+            // Pipeline self-test
+            ui.collapsing(tr(lang, "Self-test"), |ui| {
+                ui.label(tr(
+                    lang,
+                    "Runs a generated tone through decoding and resampling and loads the selected model on the CPU, to tell a problem with the app or model apart from one with an audio file.",
+                ));
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            self.self_test_receiver.is_none(),
+                            egui::Button::new(tr(lang, "Run self-test")),
+                        )
+                        .clicked()
                     {
-                        self.audio_path = Some(path);
+                        self.start_self_test();
+                    }
+                    if self.self_test_receiver.is_some() {
+                        ui.spinner();
                     }
+                });
+                if !self.self_test_results.is_empty() {
+                    egui::Grid::new("self_test_results")
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            for stage in &self.self_test_results {
+                                ui.label(tr(lang, stage.name));
+                                match stage.result {
+                                    Ok(ref detail) => {
+                                        ui.colored_label(egui::Color32::GREEN, tr(lang, "Pass"));
+                                        ui.label(detail);
+                                    }
+                                    Err(ref e) => {
+                                        ui.colored_label(egui::Color32::RED, tr(lang, "Fail"));
+                                        ui.label(e);
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
                 }
             });
 
-            ui.add_space(10.0);
+            // Versions and build details for bug reports
+            ui.collapsing(tr(lang, "About"), |ui| {
+                egui::Grid::new("about").num_columns(2).show(ui, |ui| {
+                    ui.label(tr(lang, "Version:"));
+                    ui.label(env!("CARGO_PKG_VERSION"));
+                    ui.end_row();
+                    ui.label("whisper-rs:");
+                    ui.label(format!("{} (whisper-rs-sys {})", WHISPER_RS_VERSION, WHISPER_RS_SYS_VERSION));
+                    ui.end_row();
+                    ui.label(tr(lang, "GPU backend:"));
+                    ui.label(GPU_BACKEND.unwrap_or("none"));
+                    ui.end_row();
+                    ui.label(tr(lang, "Model:"));
+                    match self.model_path.as_deref() {
+                        Some(path) => match model_format_problem(path) {
+                            Some(problem) => {
+                                ui.colored_label(egui::Color32::RED, problem);
+                            }
+                            None => {
+                                ui.label(tr(lang, "Format supported"));
+                            }
+                        },
+                        None => {
+                            ui.weak(tr(lang, "None selected"));
+                        }
+                    }
+                    ui.end_row();
+                });
+                let system_info = whisper_system_info();
+                ui.weak(&system_info);
+                if ui.button(tr(lang, "Copy details")).clicked() {
+                    ui.ctx().copy_text(format!(
+                        "whisper-transcribe {}\nwhisper-rs {} (whisper-rs-sys {})\nGPU backend: {}\n{}",
+                        env!("CARGO_PKG_VERSION"),
+                        WHISPER_RS_VERSION,
+                        WHISPER_RS_SYS_VERSION,
+                        GPU_BACKEND.unwrap_or("none"),
+                        system_info
+                    ));
+                }
+            });
 
-            // Drag & drop hint with supported formats
-            ui.label("Drag & drop audio files (MP3, WAV, FLAC, OGG, M4A, AAC, WMA, Opus)");
+            // Batch queue
+            ui.collapsing(tr(lang, "Batch"), |ui| {
+                self.batch_ui(ui, lang);
+            });
 
             ui.add_space(10.0);
 
@@ -354,357 +4083,216 @@ impl eframe::App for WhisperApp {
             let can_transcribe = self.model_path.is_some()
                 && self.audio_path.is_some()
                 && self.status != TranscribeStatus::Loading
-                && self.status != TranscribeStatus::Transcribing;
+                && self.status != TranscribeStatus::Transcribing
+                && self.status != TranscribeStatus::Verifying
+                && self.batch_receiver.is_none();
+            let larger_model = self
+                .model_path
+                .as_deref()
+                .and_then(|path| model_list_idx(&self.models, path))
+                .map(|idx| idx + 1)
+                .filter(|idx| *idx < self.models.len() && can_transcribe && self.download_receiver.is_none());
+            let mut transcribe_larger = None;
 
-            ui.horizontal(|ui| {
+            control_row(ui, self.compact, |ui| {
                 if ui
-                    .add_enabled(can_transcribe, egui::Button::new("Transcribe"))
+                    .add_enabled(can_transcribe, egui::Button::new(tr(lang, "Transcribe")))
                     .clicked()
                 {
+                    self.retime_cues = None;
                     self.start_transcription();
                 }
+                if ui
+                    .add_enabled(can_transcribe, egui::Button::new(tr(lang, "Re-time subtitles...")))
+                    .on_hover_text(tr(
+                        lang,
+                        "Transcribe, then give the cues of an existing SRT file the new timing, keeping their text",
+                    ))
+                    .clicked()
+                {
+                    self.retime_subtitles();
+                }
+
+                if self.receiver.is_some() && ui.button(tr(lang, "Cancel")).clicked() {
+                    self.transcribe_cancel.store(true, Ordering::Relaxed);
+                }
+
+                // Offer to start once the model being downloaded is ready
+                let is_downloading = self.download_receiver.is_some();
+                if is_downloading && self.audio_path.is_some() && self.receiver.is_none() {
+                    ui.checkbox(&mut self.transcribe_after_download, tr(lang, "Transcribe when download finishes"));
+                }
 
                 // Status indicator
                 match &self.status {
                     TranscribeStatus::Idle => {}
                     TranscribeStatus::Loading => {
                         ui.spinner();
-                        ui.label("Loading...");
+                        ui.label(tr(lang, "Loading..."));
                     }
                     TranscribeStatus::Transcribing => {
                         ui.spinner();
-                        ui.label("Transcribing...");
+                        ui.label(tr(lang, "Transcribing..."));
+                        if let Some(percent) = self.progress {
+                            ui.weak(format!("{}%", percent));
+                        }
+                    }
+                    TranscribeStatus::Verifying => {
+                        ui.spinner();
+                        ui.label(tr(lang, "Verifying on CPU..."));
                     }
                     TranscribeStatus::Done => {
-                        ui.label("Done!");
+                        ui.label(tr(lang, "Done!"));
+                        if self.source_deleted {
+                            ui.weak(tr(lang, "(source audio deleted)"));
+                        }
+                        if let Some(stats) = self.last_stats {
+                            ui.weak(stats.summary());
+                        }
+                        if let Some(wpm) = words_per_minute(&self.segments) {
+                            ui.weak(format!("{:.0} {}", wpm, tr(lang, "words per minute")))
+                                .on_hover_text(tr(lang, "Words spoken divided by the time inside segments"));
+                        }
+                        if let Some(idx) = larger_model {
+                            if ui
+                                .button(format!("{} {}", tr(lang, "Transcribe again with"), self.models[idx].name))
+                                .on_hover_text(tr(lang, "Same audio and settings; downloads the model first if needed"))
+                                .clicked()
+                            {
+                                transcribe_larger = Some(idx);
+                            }
+                        }
+                    }
+                    TranscribeStatus::Cancelled => {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 152, 0),
+                            tr(lang, "(partial — cancelled)"),
+                        );
                     }
                     TranscribeStatus::Error(e) => {
-                        ui.colored_label(egui::Color32::RED, format!("Error: {}", e));
+                        ui.colored_label(egui::Color32::RED, format!("{} {}", tr(lang, "Error:"), e));
                     }
                 }
+
+                if let Some(ref w) = self.transcribe_warning {
+                    ui.colored_label(egui::Color32::from_rgb(255, 152, 0), w);
+                }
             });
+            if let Some(idx) = transcribe_larger {
+                self.transcribe_with_model(idx);
+            }
 
             ui.add_space(10.0);
 
             // Output area
             ui.separator();
-            ui.label("Transcription:");
-
-            egui::ScrollArea::vertical()
-                .max_height(250.0)
-                .show(ui, |ui| {
-                    ui.add(
-                        egui::TextEdit::multiline(&mut self.transcription.as_str())
-                            .desired_width(f32::INFINITY)
-                            .desired_rows(10),
-                    );
-                });
-
-            ui.add_space(10.0);
-
-            // Action buttons
-            ui.horizontal(|ui| {
-                if ui
-                    .add_enabled(!self.transcription.is_empty(), egui::Button::new("Copy"))
-                    .clicked()
-                {
-                    self.copy_to_clipboard();
-                }
-                if ui
-                    .add_enabled(!self.transcription.is_empty(), egui::Button::new("Save..."))
-                    .clicked()
-                {
-                    self.save_to_file();
-                }
-                if ui.button("Clear").clicked() {
-                    self.transcription.clear();
-                    self.status = TranscribeStatus::Idle;
-                }
-            });
+            self.output_ui(ui, lang);
         });
 
         // Bottom status bar
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                // Show CUDA availability
-                if self.cuda_available {
-                    ui.colored_label(
-                        egui::Color32::from_rgb(76, 175, 80),
-                        "CUDA Available",
-                    );
-                } else {
-                    ui.colored_label(
-                        egui::Color32::from_rgb(255, 152, 0),
-                        "CUDA Not Found (CPU mode)",
-                    );
+                // Show GPU backend availability
+                match GPU_BACKEND {
+                    Some(backend) if self.gpu_available => {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(76, 175, 80),
+                            format!("{} Available", backend),
+                        );
+                    }
+                    Some(backend) => {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 152, 0),
+                            format!("{} Not Found (CPU mode)", backend),
+                        );
+                    }
+                    None => {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 152, 0),
+                            "CPU-only build",
+                        );
+                    }
                 }
 
                 ui.separator();
 
                 // Show runtime status
                 let (icon, text, color) = match self.using_gpu {
-                    Some(true) => ("⚡", "Running on GPU", egui::Color32::from_rgb(76, 175, 80)),
-                    Some(false) => ("💻", "Running on CPU", egui::Color32::from_rgb(255, 152, 0)),
-                    None => ("○", "Ready", egui::Color32::GRAY),
+                    Some(true) => (
+                        "⚡",
+                        match self.gpu_device {
+                            Some(ref device) => format!("{} ({})", tr(lang, "Running on GPU"), device),
+                            None => tr(lang, "Running on GPU").to_string(),
+                        },
+                        egui::Color32::from_rgb(76, 175, 80),
+                    ),
+                    Some(false) => (
+                        "💻",
+                        tr(lang, "Running on CPU").to_string(),
+                        egui::Color32::from_rgb(255, 152, 0),
+                    ),
+                    None => ("○", tr(lang, "Ready").to_string(), egui::Color32::GRAY),
                 };
                 ui.colored_label(color, format!("{} {}", icon, text));
-            });
-        });
-    }
-}
 
-fn run_transcription(model_path: PathBuf, audio_path: PathBuf, tx: Sender<TranscribeMessage>) {
-    let result = (|| -> Result<String> {
-        tx.send(TranscribeMessage::Status("Loading model...".to_string()))
-            .ok();
-
-        // Try GPU first, fallback to CPU if it fails
-        let (ctx, using_gpu) = {
-            let mut ctx_params = WhisperContextParameters::default();
-            ctx_params.use_gpu(true);
-
-            match WhisperContext::new_with_params(
-                model_path.to_str().context("Invalid model path")?,
-                ctx_params,
-            ) {
-                Ok(c) => (c, true),
-                Err(_) => {
-                    // Fallback to CPU
-                    let ctx_params = WhisperContextParameters::default();
-                    let c = WhisperContext::new_with_params(
-                        model_path.to_str().context("Invalid model path")?,
-                        ctx_params,
-                    )
-                    .context("Failed to load Whisper model")?;
-                    (c, false)
+                if let Some(ref detected) = self.detected_language {
+                    ui.separator();
+                    ui.label(format!("{} {}", tr(lang, "Detected:"), detected));
                 }
-            }
-        };
-
-        tx.send(TranscribeMessage::GpuStatus(using_gpu)).ok();
-
-        tx.send(TranscribeMessage::Status("Loading audio...".to_string()))
-            .ok();
-
-        let audio_data = load_audio_to_mono_16khz(&audio_path)?;
 
-        tx.send(TranscribeMessage::Status("Transcribing...".to_string()))
-            .ok();
-
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
-
-        let mut state = ctx.create_state().context("Failed to create state")?;
-        state
-            .full(params, &audio_data)
-            .context("Failed to transcribe audio")?;
-
-        let num_segments = state.full_n_segments();
-        let mut result = String::new();
-
-        for i in 0..num_segments {
-            if let Some(segment) = state.get_segment(i) {
-                if let Ok(text) = segment.to_str_lossy() {
-                    result.push_str(&text);
+                if self.receiver.is_some()
+                    || self.download_receiver.is_some()
+                    || self.benchmark_receiver.is_some()
+                    || self.batch_receiver.is_some()
+                {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr(lang, "Abort all")).clicked() {
+                            self.abort_all();
+                        }
+                    });
                 }
-            }
-        }
-
-        Ok(result.trim().to_string())
-    })();
-
-    match result {
-        Ok(text) => {
-            tx.send(TranscribeMessage::Done(text)).ok();
-        }
-        Err(e) => {
-            tx.send(TranscribeMessage::Error(e.to_string())).ok();
-        }
+            });
+        });
     }
 }
 
-fn run_download(url: String, dest_path: PathBuf, tx: Sender<DownloadMessage>) {
-    let rt = tokio::runtime::Runtime::new().unwrap();
-    rt.block_on(async {
-        let result = download_model(&url, &dest_path, &tx).await;
-        if let Err(e) = result {
-            tx.send(DownloadMessage::Error(e.to_string())).ok();
-        }
-    });
+/// Show a desktop notification so long runs aren't missed while working elsewhere
+fn notify_transcription_done(audio_path: Option<&PathBuf>) {
+    let body = match audio_path.and_then(|p| p.file_name()) {
+        Some(name) => format!("Finished transcribing {}", name.to_string_lossy()),
+        None => "Transcription finished".to_string(),
+    };
+    let _ = notify_rust::Notification::new()
+        .summary("Whisper Transcribe")
+        .body(&body)
+        .show();
 }
 
-async fn download_model(
-    url: &str,
-    dest_path: &PathBuf,
-    tx: &Sender<DownloadMessage>,
-) -> Result<()> {
-    // Ensure models directory exists
-    if let Some(parent) = dest_path.parent() {
-        std::fs::create_dir_all(parent).context("Failed to create models directory")?;
-    }
-
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .context("Failed to start download")?;
-
-    if !response.status().is_success() {
-        bail!("Download failed: HTTP {}", response.status());
-    }
-
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded: u64 = 0;
-
-    let mut file = File::create(dest_path).context("Failed to create model file")?;
-    let mut stream = response.bytes_stream();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.context("Error downloading chunk")?;
-        file.write_all(&chunk).context("Failed to write to file")?;
-        downloaded += chunk.len() as u64;
-        tx.send(DownloadMessage::Progress {
-            downloaded,
-            total: total_size,
-        })
-        .ok();
-    }
-
-    // Get absolute path for the model
-    let abs_path = dest_path
-        .canonicalize()
-        .unwrap_or_else(|_| dest_path.clone());
-    tx.send(DownloadMessage::Done(abs_path)).ok();
-    Ok(())
+/// Peak absolute level in each of `buckets` equal slices of the samples
+fn waveform_peaks(samples: &[f32], buckets: usize) -> Vec<f32> {
+    let chunk = samples.len().div_ceil(buckets).max(1);
+    samples
+        .chunks(chunk)
+        .map(|c| c.iter().fold(0.0f32, |peak, s| peak.max(s.abs())))
+        .collect()
 }
 
-/// Load any supported audio file and convert to mono 16kHz f32 samples
-fn load_audio_to_mono_16khz(path: &PathBuf) -> Result<Vec<f32>> {
-    let file = File::open(path).context("Failed to open audio file")?;
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
-
-    // Create a hint based on file extension
-    let mut hint = Hint::new();
-    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-        hint.with_extension(ext);
-    }
-
-    // Probe the media source
-    let format_opts = FormatOptions::default();
-    let metadata_opts = MetadataOptions::default();
-    let probed = symphonia::default::get_probe()
-        .format(&hint, mss, &format_opts, &metadata_opts)
-        .context("Unsupported audio format")?;
-
-    let mut format = probed.format;
-
-    // Find the first audio track
-    let track = format
-        .tracks()
-        .iter()
-        .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
-        .context("No audio track found")?;
-
-    let track_id = track.id;
-    let sample_rate = track
-        .codec_params
-        .sample_rate
-        .context("Unknown sample rate")?;
-    let channels = track
-        .codec_params
-        .channels
-        .context("Unknown channel count")?
-        .count();
-
-    // Create decoder
-    let decoder_opts = DecoderOptions::default();
-    let mut decoder = symphonia::default::get_codecs()
-        .make(&track.codec_params, &decoder_opts)
-        .context("Failed to create decoder")?;
-
-    // Decode all packets
-    let mut all_samples: Vec<f32> = Vec::new();
-
-    loop {
-        let packet = match format.next_packet() {
-            Ok(packet) => packet,
-            Err(symphonia::core::errors::Error::IoError(ref e))
-                if e.kind() == std::io::ErrorKind::UnexpectedEof =>
-            {
-                break;
-            }
-            Err(e) => bail!("Error reading packet: {}", e),
-        };
-
-        if packet.track_id() != track_id {
-            continue;
-        }
-
-        let decoded = match decoder.decode(&packet) {
-            Ok(decoded) => decoded,
-            Err(symphonia::core::errors::Error::DecodeError(_)) => continue,
-            Err(e) => bail!("Decode error: {}", e),
-        };
-
-        let spec = *decoded.spec();
-        let duration = decoded.capacity() as usize;
-
-        let mut sample_buf = SampleBuffer::<f32>::new(duration as u64, spec);
-        sample_buf.copy_interleaved_ref(decoded);
-
-        all_samples.extend_from_slice(sample_buf.samples());
-    }
-
-    if all_samples.is_empty() {
-        bail!("No audio samples decoded");
-    }
-
-    // Convert to mono if stereo/multi-channel
-    let mono_samples = if channels > 1 {
-        all_samples
-            .chunks(channels)
-            .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
-            .collect()
-    } else {
-        all_samples
+/// "name — description" for the model combos; built-in descriptions are translated when a language is given
+fn model_label(model: &ModelInfo, lang: Option<UiLanguage>) -> String {
+    let builtin = WHISPER_MODELS.iter().find(|(_, _, about)| *about == model.description);
+    let about = match (lang, builtin) {
+        (Some(lang), Some((_, _, about))) => tr(lang, about),
+        _ => &model.description,
     };
-
-    // Resample to 16kHz if needed
-    let target_rate = 16000;
-    if sample_rate == target_rate {
-        Ok(mono_samples)
+    if about.is_empty() {
+        model.name.clone()
     } else {
-        resample_audio(&mono_samples, sample_rate, target_rate)
+        format!("{} — {}", model.name, about)
     }
 }
 
-/// High-quality resampling using rubato
-fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
-    let params = SincInterpolationParameters {
-        sinc_len: 256,
-        f_cutoff: 0.95,
-        interpolation: SincInterpolationType::Linear,
-        oversampling_factor: 256,
-        window: WindowFunction::BlackmanHarris2,
-    };
-
-    let mut resampler = SincFixedIn::<f32>::new(
-        to_rate as f64 / from_rate as f64,
-        2.0,
-        params,
-        samples.len(),
-        1,
-    )
-    .context("Failed to create resampler")?;
-
-    let waves_in = vec![samples.to_vec()];
-    let waves_out = resampler
-        .process(&waves_in, None)
-        .context("Failed to resample audio")?;
-
-    Ok(waves_out.into_iter().next().unwrap_or_default())
+/// Format seconds as M:SS.s for the selection readout
+fn format_clock(secs: f32) -> String {
+    format!("{}:{:04.1}", (secs / 60.0) as u32, secs % 60.0)
 }
+
@@ -0,0 +1,289 @@
+//! Rule-based cleanup of transcript text: spelled-out numbers, punctuation spacing and capitalization
+
+/// Language-specific rules to apply on top of the generic cleanup
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextLanguage {
+    English,
+    // French keeps a space before ! ? ; :
+    French,
+    Other,
+}
+
+/// Normalize one segment's text, keeping whisper's leading space
+pub fn normalize_text(text: &str, language: TextLanguage) -> String {
+    let leading_space = text.starts_with(' ');
+    let mut out = if language == TextLanguage::English {
+        normalize_english_numbers(text)
+    } else {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    };
+    out = fix_punctuation_spacing(&out, language == TextLanguage::French);
+    if leading_space && !out.is_empty() {
+        out.insert(0, ' ');
+    }
+    out
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum NumberWord {
+    Unit(u64),
+    Teen(u64),
+    Tens(u64),
+    Hundred,
+    Scale(u64),
+}
+
+fn number_word(word: &str) -> Option<NumberWord> {
+    let word = word.to_lowercase();
+    let unit = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let teen = [
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    let tens = [
+        "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+    if let Some(i) = unit.iter().position(|w| *w == word) {
+        return Some(NumberWord::Unit(i as u64));
+    }
+    if let Some(i) = teen.iter().position(|w| *w == word) {
+        return Some(NumberWord::Teen(10 + i as u64));
+    }
+    if let Some(i) = tens.iter().position(|w| *w == word) {
+        return Some(NumberWord::Tens(20 + 10 * i as u64));
+    }
+    match word.as_str() {
+        "hundred" => Some(NumberWord::Hundred),
+        "thousand" => Some(NumberWord::Scale(1_000)),
+        "million" => Some(NumberWord::Scale(1_000_000)),
+        "billion" => Some(NumberWord::Scale(1_000_000_000)),
+        _ => None,
+    }
+}
+
+/// Number words in a token, allowing hyphenated forms like "twenty-four"
+fn token_number_words(token: &str) -> Option<Vec<NumberWord>> {
+    token.split('-').map(number_word).collect()
+}
+
+/// Split a token into its word and any trailing punctuation
+fn split_trailing_punctuation(token: &str) -> (&str, &str) {
+    let end = token
+        .trim_end_matches(|c: char| c.is_ascii_punctuation())
+        .len();
+    token.split_at(end)
+}
+
+/// Values spoken by a run of number words; a word that can't continue the
+/// current number starts a new one ("twenty twenty four" gives 20 and 24)
+fn spoken_values(words: &[NumberWord]) -> Vec<u64> {
+    let mut values = Vec::new();
+    let mut total = 0;
+    let mut current = 0;
+    let mut last: Option<NumberWord> = None;
+
+    for &word in words {
+        let continues = match (last, word) {
+            (None, _) => true,
+            (Some(NumberWord::Tens(_)), NumberWord::Unit(u)) => u != 0,
+            (
+                Some(NumberWord::Hundred | NumberWord::Scale(_)),
+                NumberWord::Unit(_) | NumberWord::Teen(_) | NumberWord::Tens(_),
+            ) => true,
+            (Some(NumberWord::Unit(_) | NumberWord::Teen(_)), NumberWord::Hundred) => true,
+            (
+                Some(
+                    NumberWord::Unit(_)
+                    | NumberWord::Teen(_)
+                    | NumberWord::Tens(_)
+                    | NumberWord::Hundred,
+                ),
+                NumberWord::Scale(_),
+            ) => true,
+            _ => false,
+        };
+        if !continues {
+            values.push(total + current);
+            total = 0;
+            current = 0;
+        }
+        match word {
+            NumberWord::Unit(n) | NumberWord::Teen(n) | NumberWord::Tens(n) => current += n,
+            NumberWord::Hundred => current = current.max(1) * 100,
+            NumberWord::Scale(scale) => {
+                total += current.max(1) * scale;
+                current = 0;
+            }
+        }
+        last = Some(word);
+    }
+    if last.is_some() {
+        values.push(total + current);
+    }
+
+    // Two two-digit numbers read as a year: "nineteen eighty four" → 1984. Only centuries
+    // that come up in speech, so "fifty fifty" and "sixty forty" stay two numbers
+    if let [first, second] = values[..] {
+        if (11..=20).contains(&first) && (10..=99).contains(&second) {
+            return vec![first * 100 + second];
+        }
+    }
+    values
+}
+
+/// Replace runs of English number words with digits, leaving lone words under ten spelled out
+fn normalize_english_numbers(text: &str) -> String {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let mut words: Vec<NumberWord> = Vec::new();
+        let mut j = i;
+        let mut trailing = "";
+        while j < tokens.len() {
+            let (word, punct) = split_trailing_punctuation(tokens[j]);
+            let parsed = if word.eq_ignore_ascii_case("and")
+                && matches!(
+                    words.last(),
+                    Some(NumberWord::Hundred | NumberWord::Scale(_))
+                )
+                && j + 1 < tokens.len()
+                && token_number_words(split_trailing_punctuation(tokens[j + 1]).0).is_some()
+            {
+                Some(Vec::new())
+            } else {
+                token_number_words(word)
+            };
+            let Some(parsed) = parsed else {
+                break;
+            };
+            words.extend(parsed);
+            j += 1;
+            // Punctuation ends the number
+            if !punct.is_empty() {
+                trailing = punct;
+                break;
+            }
+        }
+
+        if words.is_empty() {
+            out.push(tokens[i].to_string());
+            i += 1;
+            continue;
+        }
+
+        let values = spoken_values(&words);
+        if values.len() == 1 && values[0] < 10 && j - i == 1 {
+            out.push(tokens[i].to_string());
+        } else {
+            let digits: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+            out.push(format!("{}{}", digits.join(" "), trailing));
+        }
+        i = j;
+    }
+
+    out.join(" ")
+}
+
+/// Remove spaces before punctuation and add a missing one after it
+fn fix_punctuation_spacing(text: &str, french: bool) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_whitespace() {
+            if !out.ends_with(' ') && !out.is_empty() {
+                out.push(' ');
+            }
+            continue;
+        }
+        let tight = matches!(c, ',' | '.') || (!french && matches!(c, '!' | '?' | ';' | ':'));
+        if tight {
+            while out.ends_with(' ') {
+                out.pop();
+            }
+        }
+        out.push(c);
+        // "a,b" → "a, b", but leave "3,000" and "1.5" alone
+        if matches!(c, ',' | ';' | '!' | '?') && chars.get(i + 1).is_some_and(|n| n.is_alphabetic())
+        {
+            out.push(' ');
+        }
+    }
+
+    out.trim_end().to_string()
+}
+
+// Abbreviations whose period doesn't end an English sentence
+const ENGLISH_ABBREVIATIONS: &[&str] = &["e.g.", "i.e.", "vs.", "mr.", "mrs.", "ms.", "dr."];
+
+/// Capitalize the first letter of each sentence and, in English, the pronoun "I", for models
+/// that write in lowercase. `sentence_start` says whether the text opens a sentence; the
+/// returned flag says whether the text after it does.
+pub fn fix_capitalization(text: &str, sentence_start: bool, language: TextLanguage) -> (String, bool) {
+    let mut out = String::with_capacity(text.len());
+    let mut capitalize_next = sentence_start;
+
+    // Split on single spaces so the spacing comes back out unchanged
+    for (i, word) in text.split(' ').enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
+        let mut word = word.to_string();
+        if let Some(pos) = word.find(char::is_alphanumeric) {
+            if capitalize_next {
+                word = uppercase_at(&word, pos);
+                capitalize_next = false;
+            }
+            if language == TextLanguage::English && is_english_i(&word) {
+                word = uppercase_at(&word, pos);
+            }
+        }
+        if ends_sentence(&word, language) {
+            capitalize_next = true;
+        }
+        out.push_str(&word);
+    }
+    (out, capitalize_next)
+}
+
+/// The word with the letter at byte `pos` in upper case
+fn uppercase_at(word: &str, pos: usize) -> String {
+    let mut chars = word[pos..].chars();
+    let Some(first) = chars.next() else {
+        return word.to_string();
+    };
+    format!("{}{}{}", &word[..pos], first.to_uppercase(), chars.as_str())
+}
+
+/// "i" on its own or in a contraction like "i'm" or "i've"
+fn is_english_i(word: &str) -> bool {
+    let bare = word
+        .trim_matches(|c: char| !c.is_alphanumeric() && c != '\'' && c != '’')
+        .to_lowercase()
+        .replace('’', "'");
+    matches!(bare.as_str(), "i" | "i'm" | "i've" | "i'll" | "i'd")
+}
+
+fn ends_sentence(word: &str, language: TextLanguage) -> bool {
+    let word = word.trim_end_matches(['"', '\'', ')', '”', '’', '»']);
+    // An ellipsis usually trails off mid-sentence
+    if word.ends_with("..") || word.ends_with('…') {
+        return false;
+    }
+    if language == TextLanguage::English && ENGLISH_ABBREVIATIONS.contains(&word.to_lowercase().as_str()) {
+        return false;
+    }
+    word.ends_with(['.', '!', '?', '。', '！', '？'])
+}
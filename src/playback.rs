@@ -0,0 +1,44 @@
+//! Play a stretch of the decoded audio, so a segment can be heard while its text is corrected
+
+use anyhow::{Context, Result};
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, OutputStreamHandle, Sink};
+use whisper_transcribe::WHISPER_SAMPLE_RATE;
+
+/// Audio output, opened on first use and kept open for later clips
+pub struct Player {
+    // Dropping the stream closes the device, so it lives as long as the player
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+    sink: Option<Sink>,
+}
+
+impl Player {
+    pub fn new() -> Result<Self> {
+        let (stream, handle) = OutputStream::try_default().context("No audio output device")?;
+        Ok(Self {
+            _stream: stream,
+            handle,
+            sink: None,
+        })
+    }
+
+    /// Play 16kHz mono samples, cutting off whatever was playing
+    pub fn play(&mut self, samples: &[f32]) -> Result<()> {
+        self.stop();
+        let sink = Sink::try_new(&self.handle).context("Failed to start playback")?;
+        sink.append(SamplesBuffer::new(1, WHISPER_SAMPLE_RATE as u32, samples));
+        self.sink = Some(sink);
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(sink) = self.sink.take() {
+            sink.stop();
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.sink.as_ref().is_some_and(|sink| !sink.empty())
+    }
+}
@@ -0,0 +1,160 @@
+//! Local HTTP service for other tools on the same machine: POST audio, get the transcript as JSON
+
+use anyhow::{Context, Result, anyhow};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tiny_http::{Header, Method, Request, Response, Server};
+use whisper_transcribe::{
+    TranscribeOptions, language_code, temp_path, transcribe, transcript_to_json,
+};
+
+// Larger uploads are refused rather than filling the temp folder
+const MAX_UPLOAD_BYTES: u64 = 2 << 30;
+
+/// Answer transcription requests on localhost, one at a time, until the process is stopped.
+/// Uploads are written to `temp_dir`, or the system temp folder when that's None.
+pub fn serve(
+    model: &Path,
+    port: u16,
+    options: &TranscribeOptions,
+    temp_dir: Option<&Path>,
+) -> Result<()> {
+    // Bound to loopback only, so nothing off this machine can reach it
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|e| anyhow!("Failed to listen on port {}: {}", port, e))?;
+    eprintln!("Listening on http://127.0.0.1:{}/transcribe", port);
+
+    let json_type: Header = "Content-Type: application/json"
+        .parse()
+        .map_err(|_| anyhow!("Invalid response header"))?;
+    for mut request in server.incoming_requests() {
+        let (status, body) = match handle(&mut request, port, model, options, temp_dir) {
+            Ok(json) => (200, json),
+            Err((status, message)) => (status, serde_json::json!({ "error": message }).to_string()),
+        };
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(json_type.clone());
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to send response: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Transcribe the file named by `?path=`, or the audio uploaded as the request body.
+/// `?language=` takes a code or name and skips auto-detection.
+fn handle(
+    request: &mut Request,
+    port: u16,
+    model: &Path,
+    options: &TranscribeOptions,
+    temp_dir: Option<&Path>,
+) -> Result<String, (u16, String)> {
+    let url = reqwest::Url::parse(&format!("http://127.0.0.1{}", request.url()))
+        .map_err(|e| (400, format!("Invalid URL: {}", e)))?;
+    if url.path() != "/transcribe" {
+        return Err((404, "Not found; POST audio to /transcribe".to_string()));
+    }
+    if *request.method() != Method::Post {
+        return Err((405, "Use POST".to_string()));
+    }
+    // A web page that rebinds its own domain to 127.0.0.1 could otherwise read any file's
+    // transcript through ?path=, but its requests still carry that domain as the Host
+    let host = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Host"))
+        .map(|h| h.value.as_str());
+    if !host.is_some_and(|host| is_local_host(host, port)) {
+        return Err((
+            403,
+            "Requests must be addressed to 127.0.0.1 or localhost".to_string(),
+        ));
+    }
+
+    let mut options = options.clone();
+    let mut path = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "path" => path = Some(PathBuf::from(value.as_ref())),
+            "language" => {
+                options.language = Some(
+                    language_code(&value)
+                        .ok_or_else(|| (400, format!("Unknown language: {}", value)))?,
+                )
+            }
+            _ => return Err((400, format!("Unknown parameter: {}", key))),
+        }
+    }
+
+    let result = match path {
+        Some(path) => {
+            if !path.is_file() {
+                return Err((400, format!("No such file: {}", path.display())));
+            }
+            transcribe(model, &path, &options)
+        }
+        None => {
+            let upload = save_upload(request, temp_dir)?;
+            let result = transcribe(model, &upload, &options);
+            let _ = std::fs::remove_file(&upload);
+            result
+        }
+    };
+    result
+        .map(|transcript| transcript_to_json(&transcript))
+        .map_err(|e| (500, format!("{:#}", e)))
+}
+
+/// A Host header naming this service on loopback
+fn is_local_host(host: &str, port: u16) -> bool {
+    let name = host.strip_suffix(&format!(":{}", port)).unwrap_or(host);
+    name == "127.0.0.1" || name.eq_ignore_ascii_case("localhost")
+}
+
+/// Write the request body to a temp file for the decoder
+fn save_upload(request: &mut Request, temp_dir: Option<&Path>) -> Result<PathBuf, (u16, String)> {
+    match request.body_length() {
+        Some(0) => {
+            return Err((
+                400,
+                "Pass ?path=<audio file> or send the audio as the request body".to_string(),
+            ));
+        }
+        Some(length) if length as u64 > MAX_UPLOAD_BYTES => return Err(upload_too_large()),
+        _ => {}
+    }
+    // Requests are handled one at a time, so a single name per process is enough
+    let path = temp_path(temp_dir, "upload");
+    match write_upload(request, &path) {
+        // A chunked body has no length up front, so one byte past the limit gives it away
+        Ok(bytes) if bytes > MAX_UPLOAD_BYTES => {
+            let _ = std::fs::remove_file(&path);
+            Err(upload_too_large())
+        }
+        Ok(_) => Ok(path),
+        Err(e) => {
+            let _ = std::fs::remove_file(&path);
+            Err((400, format!("{:#}", e)))
+        }
+    }
+}
+
+fn upload_too_large() -> (u16, String) {
+    (
+        413,
+        format!("Uploads are limited to {} MiB", MAX_UPLOAD_BYTES >> 20),
+    )
+}
+
+/// Copy the body to `path`, reading at most one byte past the limit; returns the bytes written
+fn write_upload(request: &mut Request, path: &Path) -> Result<u64> {
+    let mut file = File::create(path).context("Failed to create temp file for the upload")?;
+    std::io::copy(
+        &mut request.as_reader().take(MAX_UPLOAD_BYTES + 1),
+        &mut file,
+    )
+    .context("Failed to read the uploaded audio")
+}
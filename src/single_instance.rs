@@ -0,0 +1,92 @@
+//! Hand files opened from the file manager to the window that's already running
+
+use interprocess::local_socket::{
+    GenericNamespaced, ListenerOptions, Stream, ToNsName, prelude::*,
+};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+
+/// The socket is machine-wide (an abstract socket on Linux, a named pipe on Windows), so each
+/// user gets their own: on Unix by user id, on Windows by login session, which belongs to one
+/// user. Peers from anywhere else are refused on both ends of the connection.
+fn socket_name() -> String {
+    format!("whisper-transcribe-{}.sock", current_owner())
+}
+
+#[cfg(unix)]
+fn current_owner() -> u32 {
+    // SAFETY: geteuid has no preconditions and can't fail
+    unsafe { libc::geteuid() }
+}
+
+#[cfg(windows)]
+fn current_owner() -> u32 {
+    session_of(std::process::id()).unwrap_or(u32::MAX)
+}
+
+/// The login session a process runs in
+#[cfg(windows)]
+fn session_of(process_id: u32) -> Option<u32> {
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn ProcessIdToSessionId(process_id: u32, session_id: *mut u32) -> i32;
+    }
+    let mut session = 0;
+    // SAFETY: `session` is a valid place for the result
+    (unsafe { ProcessIdToSessionId(process_id, &mut session) } != 0).then_some(session)
+}
+
+/// Whether the other end of the connection runs as the same user as this process
+fn same_owner(stream: &Stream) -> bool {
+    let Ok(creds) = stream.peer_creds() else {
+        return false;
+    };
+    #[cfg(unix)]
+    let owner = creds.euid();
+    #[cfg(windows)]
+    let owner = creds.pid().and_then(session_of);
+    owner == Some(current_owner())
+}
+
+/// Send the files to a running instance; false when none of this user's is listening
+pub fn send_to_running(paths: &[PathBuf]) -> bool {
+    let Ok(name) = socket_name().to_ns_name::<GenericNamespaced>() else {
+        return false;
+    };
+    let Ok(mut stream) = Stream::connect(name) else {
+        return false;
+    };
+    if !same_owner(&stream) {
+        return false;
+    }
+    // One absolute path per line
+    let message: String = paths
+        .iter()
+        .map(|p| format!("{}\n", std::path::absolute(p).unwrap_or_else(|_| p.clone()).display()))
+        .collect();
+    stream.write_all(message.as_bytes()).is_ok()
+}
+
+/// Accept files from later launches. None if another instance already owns the socket.
+pub fn listen(ctx: eframe::egui::Context) -> Option<Receiver<PathBuf>> {
+    let name = socket_name().to_ns_name::<GenericNamespaced>().ok()?;
+    let listener = ListenerOptions::new().name(name).create_sync().ok()?;
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        for stream in listener.incoming().filter_map(Result::ok) {
+            if !same_owner(&stream) {
+                continue;
+            }
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                let path = PathBuf::from(line.trim());
+                if path.is_file() && tx.send(path).is_err() {
+                    return;
+                }
+            }
+            ctx.request_repaint();
+        }
+    });
+    Some(rx)
+}
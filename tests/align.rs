@@ -0,0 +1,52 @@
+use whisper_transcribe::Segment;
+use whisper_transcribe::align::{parse_srt, retime_cues};
+
+fn segment(start: i64, end: i64, text: &str) -> Segment {
+    Segment {
+        start,
+        end,
+        text: text.to_string(),
+        confidence: 1.0,
+        words: Vec::new(),
+        speaker_turn: false,
+        no_speech_prob: 0.0,
+    }
+}
+
+#[test]
+fn parses_srt_cues() {
+    let srt = "\u{feff}1\r\n00:00:01,000 --> 00:00:02,500\r\nHello there\r\n\r\n\
+               2\r\n01:02:03.450 --> 01:02:04.000 X1:10\r\nsecond\r\nline\r\n\r\n\
+               3\r\nnot a timing line\r\n";
+    let cues = parse_srt(srt);
+    assert_eq!(cues.len(), 2);
+    assert_eq!(
+        (cues[0].start, cues[0].end, cues[0].text.as_str()),
+        (100, 250, "Hello there")
+    );
+    assert_eq!(
+        (cues[1].start, cues[1].end, cues[1].text.as_str()),
+        (372_345, 372_400, "second line")
+    );
+}
+
+#[test]
+fn retimes_cues_to_new_transcript() {
+    let cues = [
+        segment(0, 200, "The quick brown fox."),
+        segment(200, 400, "Zzz qqq."),
+        segment(400, 600, "Jumps over the lazy dog!"),
+    ];
+    // The audio gained five seconds at the start, and the recognizer misheard a word
+    let transcript = [
+        segment(500, 700, " the quick brown box"),
+        segment(900, 1100, " jumps over the lazy dog"),
+    ];
+    let retimed = retime_cues(&cues, &transcript);
+    assert_eq!(retimed.len(), 3);
+    assert_eq!((retimed[0].start, retimed[0].end), (500, 700));
+    assert_eq!(retimed[0].text, "The quick brown fox.");
+    // Not found, so it moves with the cue before it
+    assert_eq!((retimed[1].start, retimed[1].end), (700, 900));
+    assert_eq!((retimed[2].start, retimed[2].end), (900, 1100));
+}
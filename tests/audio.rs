@@ -0,0 +1,158 @@
+use std::f32::consts::PI;
+use std::path::PathBuf;
+use whisper_transcribe::{
+    AudioFilter, ResamplerOptions, WHISPER_SAMPLE_RATE, apply_audio_filter, apply_auto_gain,
+    cut_timestamp, load_audio_to_mono_16khz, remove_cuts, resample_audio, run_self_test,
+    uncut_timestamp,
+};
+
+fn sine(freq: f32, rate: u32, secs: f32) -> Vec<f32> {
+    let n = (rate as f32 * secs) as usize;
+    (0..n)
+        .map(|i| 0.5 * (2.0 * PI * freq * i as f32 / rate as f32).sin())
+        .collect()
+}
+
+fn zero_crossings(samples: &[f32]) -> usize {
+    samples
+        .windows(2)
+        .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+        .count()
+}
+
+#[test]
+fn decodes_wav_to_16khz_mono() {
+    // Half a second of a 440 Hz tone, 22050 Hz stereo
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/tone_22050hz_stereo.wav");
+    let decoded = load_audio_to_mono_16khz(&path, &ResamplerOptions::default(), false).unwrap();
+
+    assert!(!decoded.is_partial());
+    let expected = WHISPER_SAMPLE_RATE / 2;
+    assert!(
+        decoded.samples.len().abs_diff(expected) <= 1,
+        "got {} samples, expected about {}",
+        decoded.samples.len(),
+        expected
+    );
+    // Both channels carry the same tone, so the downmix keeps its frequency
+    let crossings = zero_crossings(&decoded.samples);
+    assert!(
+        (430..=450).contains(&crossings),
+        "{} zero crossings",
+        crossings
+    );
+}
+
+#[test]
+fn decodes_wav_with_wrong_extension() {
+    let source =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/tone_22050hz_stereo.wav");
+    let path = std::env::temp_dir().join(format!(
+        "whisper-transcribe-test-{}.mp3",
+        std::process::id()
+    ));
+    std::fs::copy(&source, &path).unwrap();
+    let decoded = load_audio_to_mono_16khz(&path, &ResamplerOptions::default(), false);
+    std::fs::remove_file(&path).ok();
+
+    let decoded = decoded.unwrap();
+    assert!(decoded.samples.len().abs_diff(WHISPER_SAMPLE_RATE / 2) <= 1);
+}
+
+#[test]
+fn resamples_sine_to_target_rate() {
+    let input = sine(440.0, 44100, 1.0);
+    let output = resample_audio(&input, 44100, 16000, &ResamplerOptions::default()).unwrap();
+
+    assert!(
+        output.len().abs_diff(16000) <= 1,
+        "got {} samples, expected about 16000",
+        output.len()
+    );
+    let crossings = zero_crossings(&output);
+    assert!(
+        (870..=890).contains(&crossings),
+        "{} zero crossings",
+        crossings
+    );
+    let peak = output.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    assert!((0.45..=0.55).contains(&peak), "peak {}", peak);
+}
+
+#[test]
+fn cuts_remove_audio_and_map_times() {
+    // Ten seconds, with seconds 2-3 and 5-7 cut out (given overlapping and out of order)
+    let samples = vec![0.0; WHISPER_SAMPLE_RATE * 10];
+    let cuts = [(5.0, 6.5), (2.0, 3.0), (6.0, 7.0)];
+    assert_eq!(remove_cuts(&samples, &cuts).len(), WHISPER_SAMPLE_RATE * 7);
+
+    assert_eq!(cut_timestamp(150, &cuts), 150);
+    assert_eq!(cut_timestamp(250, &cuts), 200);
+    assert_eq!(cut_timestamp(800, &cuts), 500);
+    assert_eq!(uncut_timestamp(150, &cuts), 150);
+    assert_eq!(uncut_timestamp(250, &cuts), 350);
+    assert_eq!(uncut_timestamp(500, &cuts), 800);
+}
+
+#[test]
+fn auto_gain_lifts_short_quiet_speech_but_not_loud_neighbours() {
+    let rate = WHISPER_SAMPLE_RATE as u32;
+    let peak = |s: &[f32]| s.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+    let window = WHISPER_SAMPLE_RATE / 2;
+    // Silence, half a second of quiet speech, silence, then loud speech right after quiet speech
+    let quiet: Vec<f32> = sine(300.0, rate, 0.5).iter().map(|s| s * 0.02).collect();
+    let mut samples = vec![0.0; window];
+    samples.extend(&quiet);
+    samples.extend(vec![0.0; window]);
+    samples.extend(&quiet);
+    samples.extend(sine(300.0, rate, 0.5));
+    apply_auto_gain(&mut samples, 1.0);
+
+    // The lone quiet window is lifted all the way through, edges included
+    let lone = &samples[window..window * 2];
+    assert!(
+        peak(&lone[..window / 10]) > 0.1,
+        "{}",
+        peak(&lone[..window / 10])
+    );
+    assert!(peak(&lone[window * 9 / 10..]) > 0.1);
+    // The loud window keeps its level
+    assert!((peak(&samples[window * 4..]) - 0.5).abs() < 0.01);
+}
+
+#[test]
+fn filters_cut_their_bands_and_keep_speech() {
+    let rms = |s: &[f32]| (s.iter().map(|x| x * x).sum::<f32>() / s.len() as f32).sqrt();
+    // Skip the first half second while the filters settle
+    let level = |freq: f32, filter: AudioFilter| {
+        let mut samples = sine(freq, WHISPER_SAMPLE_RATE as u32, 2.0);
+        apply_audio_filter(&mut samples, filter);
+        rms(&samples[WHISPER_SAMPLE_RATE / 2..]) / rms(&sine(freq, WHISPER_SAMPLE_RATE as u32, 1.0))
+    };
+
+    assert!(level(60.0, AudioFilter::Hum) < 0.05);
+    assert!(level(1000.0, AudioFilter::Hum) > 0.95);
+    assert!(level(100.0, AudioFilter::Telephone) < 0.05);
+    assert!(level(7000.0, AudioFilter::Telephone) < 0.05);
+    assert!(level(1000.0, AudioFilter::Telephone) > 0.8);
+    assert!(level(7500.0, AudioFilter::Hiss) < 0.3);
+    assert!(level(500.0, AudioFilter::Hiss) > 0.95);
+    assert!((level(60.0, AudioFilter::None) - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn rejects_missing_file() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/missing.wav");
+    assert!(load_audio_to_mono_16khz(&path, &ResamplerOptions::default(), false).is_err());
+}
+
+#[test]
+fn self_test_passes_audio_stages_without_a_model() {
+    let stages = run_self_test(None, &ResamplerOptions::default());
+
+    let names: Vec<&str> = stages.iter().map(|s| s.name).collect();
+    assert_eq!(names, ["Decode", "Resample", "Model"]);
+    assert!(stages[0].result.is_ok(), "{:?}", stages[0].result);
+    assert!(stages[1].result.is_ok(), "{:?}", stages[1].result);
+    assert!(stages[2].result.is_err());
+}
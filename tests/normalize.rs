@@ -0,0 +1,60 @@
+use whisper_transcribe::normalize::{TextLanguage, fix_capitalization, normalize_text};
+
+fn english(text: &str) -> String {
+    normalize_text(text, TextLanguage::English)
+}
+
+#[test]
+fn spelled_out_numbers_become_digits() {
+    assert_eq!(english(" twenty four hours"), " 24 hours");
+    assert_eq!(english("three hundred and five people"), "305 people");
+    assert_eq!(english("two thousand twenty-one, then"), "2021, then");
+    // Lone words under ten stay spelled out
+    assert_eq!(english("one of five"), "one of five");
+}
+
+#[test]
+fn only_plausible_years_are_merged() {
+    assert_eq!(english("in nineteen eighty four"), "in 1984");
+    assert_eq!(english("since twenty twenty"), "since 2020");
+    assert_eq!(english("a fifty fifty chance"), "a 50 50 chance");
+    assert_eq!(english("a sixty forty split"), "a 60 40 split");
+}
+
+#[test]
+fn punctuation_spacing_is_fixed() {
+    assert_eq!(english("well ,yes ."), "well, yes.");
+    assert_eq!(english("it costs 3,000 or 1.5"), "it costs 3,000 or 1.5");
+    assert_eq!(
+        normalize_text("Quoi ?Oui !", TextLanguage::French),
+        "Quoi ? Oui !"
+    );
+}
+
+#[test]
+fn sentences_and_i_are_capitalized() {
+    let (text, next) =
+        fix_capitalization("hello there. i think i'm done", true, TextLanguage::English);
+    assert_eq!(text, "Hello there. I think I'm done");
+    assert!(!next);
+
+    // The sentence state carries over to the next segment
+    let (text, next) = fix_capitalization(" it ends here!", false, TextLanguage::English);
+    assert_eq!(text, " it ends here!");
+    assert!(next);
+    let (text, _) = fix_capitalization(" and then", next, TextLanguage::English);
+    assert_eq!(text, " And then");
+}
+
+#[test]
+fn abbreviations_and_ellipses_dont_end_sentences() {
+    let (text, _) = fix_capitalization(
+        "ask dr. smith, e.g. tomorrow... or not",
+        true,
+        TextLanguage::English,
+    );
+    assert_eq!(text, "Ask dr. smith, e.g. tomorrow... or not");
+    // Only English capitalizes a lone "i"
+    let (text, _) = fix_capitalization("voilà i", true, TextLanguage::French);
+    assert_eq!(text, "Voilà i");
+}
@@ -0,0 +1,104 @@
+use whisper_transcribe::{
+    Segment, TimeUnit, merge_best_segments, merge_segments, segments_to_json, segments_to_jsonl,
+    split_long_segments,
+};
+
+fn segment(start: i64, end: i64, text: &str) -> Segment {
+    Segment {
+        start,
+        end,
+        text: text.to_string(),
+        confidence: 1.0,
+        words: Vec::new(),
+        speaker_turn: false,
+        no_speech_prob: 0.0,
+    }
+}
+
+#[test]
+fn merge_limit_counts_characters() {
+    // About ten characters each, but three bytes per character in UTF-8
+    let segments = [
+        segment(0, 50, " 東京は今日も晴れです"),
+        segment(50, 100, " 明日は雨が降るでしょう"),
+    ];
+    let merged = merge_segments(&segments, 200, 25);
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].end, 100);
+    assert_eq!(merge_segments(&segments, 200, 15).len(), 2);
+}
+
+#[test]
+fn splits_text_without_spaces() {
+    // Twelve seconds of Japanese with no spaces, split into pieces of at most five seconds
+    let segments = [segment(
+        0,
+        1200,
+        " 今日はとても良い天気なので公園に散歩に行きました",
+    )];
+    let split = split_long_segments(&segments, 500);
+    assert_eq!(split.len(), 3);
+    assert!(split.iter().all(|s| s.end - s.start <= 500));
+    assert_eq!((split[0].start, split[2].end), (0, 1200));
+    let text: String = split.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(text, segments[0].text);
+}
+
+#[test]
+fn keeps_segments_with_only_whitespace() {
+    let segments = [segment(0, 1200, "  ")];
+    let split = split_long_segments(&segments, 500);
+    assert_eq!(split.len(), 1);
+    assert_eq!(split[0].end, 1200);
+}
+
+#[test]
+fn ensemble_merge_handles_different_boundaries() {
+    let confident = |start, end, text, confidence| Segment {
+        confidence,
+        ..segment(start, end, text)
+    };
+    let primary = [
+        confident(0, 300, " one two three", 0.5),
+        confident(300, 600, " four five six", 0.5),
+        confident(800, 900, " nine", 0.9),
+    ];
+    // Splits the first six words elsewhere, with a middle segment spanning both primary ones
+    let other = [
+        confident(0, 150, " one", 0.9),
+        confident(150, 450, " two three four", 0.9),
+        confident(450, 600, " five six", 0.9),
+        confident(650, 750, " seven", 0.9),
+        confident(850, 950, " nein", 0.2),
+    ];
+    let texts = |segments: &[Segment]| segments.iter().map(|s| s.text.clone()).collect::<String>();
+
+    let merged = merge_best_segments(&primary, &other);
+    assert_eq!(texts(&merged), " one two three four five six seven nine");
+
+    // A less confident second run leaves the overlapping primary segments in place
+    let unsure: Vec<Segment> = other
+        .iter()
+        .map(|s| Segment {
+            confidence: 0.1,
+            ..s.clone()
+        })
+        .collect();
+    let merged = merge_best_segments(&primary, &unsure);
+    assert_eq!(texts(&merged), " one two three four five six seven nine");
+    assert_eq!(merged.len(), 4);
+}
+
+#[test]
+fn json_exports_name_the_time_unit_in_every_segment() {
+    let segments = [segment(150, 320, " hello"), segment(320, 400, " there")];
+    for line in segments_to_jsonl(&segments, TimeUnit::Milliseconds).lines() {
+        let record: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(record["time_unit"], "milliseconds");
+    }
+    let json: serde_json::Value =
+        serde_json::from_str(&segments_to_json(&segments, TimeUnit::Seconds)).unwrap();
+    assert_eq!(json["time_unit"], "seconds");
+    assert_eq!(json["segments"][1]["time_unit"], "seconds");
+    assert_eq!(json["segments"][0]["start"], 1.5);
+}
@@ -0,0 +1,38 @@
+//! Runs a real transcription, so it needs a model on disk:
+//!
+//!     WHISPER_TEST_MODEL=models/ggml-tiny.en.bin WHISPER_TEST_JFK=whisper.cpp/samples/jfk.wav cargo test
+//!
+//! jfk.wav is the sample clip that ships with whisper.cpp. Without both variables the test is skipped.
+
+use std::path::PathBuf;
+use whisper_transcribe::{TranscribeEvent, TranscribeOptions, segments_to_text, transcribe_with};
+
+#[test]
+fn transcribes_known_words() {
+    let (Some(model), Some(audio)) = (
+        std::env::var_os("WHISPER_TEST_MODEL").map(PathBuf::from),
+        std::env::var_os("WHISPER_TEST_JFK").map(PathBuf::from),
+    ) else {
+        eprintln!("WHISPER_TEST_MODEL and WHISPER_TEST_JFK not set, skipping");
+        return;
+    };
+
+    let options = TranscribeOptions {
+        language: Some("en".to_string()),
+        use_gpu: false,
+        ..Default::default()
+    };
+    let mut streamed = 0;
+    let transcript = transcribe_with(&model, &audio, &options, |event| {
+        if let TranscribeEvent::Segment(_) = event {
+            streamed += 1;
+        }
+    })
+    .unwrap();
+
+    assert!(streamed > 0);
+    let text = segments_to_text(&transcript.segments).to_lowercase();
+    for word in ["ask not", "your country"] {
+        assert!(text.contains(word), "{:?} missing from {:?}", word, text);
+    }
+}